@@ -0,0 +1,38 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut t = 0.0f32;
+
+    while lux.is_open() {
+        t += 0.02;
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        // Build the transformation once and reuse it for several shapes.
+        let arm = Affine2::translation(300.0, 300.0)
+            .rotate(t)
+            .scale(1.0 + 0.5 * t.sin(), 1.0);
+
+        for i in 0 .. 6 {
+            let piece = arm.rotate(i as f32 * 3.14159 / 3.0).translate(60.0, -10.0);
+            frame.draw(Rectangle {
+                w: 100.0, h: 20.0,
+                color: color::BLUE,
+                transform: Some(piece.into()),
+                .. Default::default()
+            }).unwrap();
+        }
+
+        // Pull the pieces back out of the combined transformation.
+        let ((x, y), rotation, (sx, _)) = arm.decompose();
+        frame.draw(Circle {
+            x: x - 10.0 * sx, y: y - 10.0 * sx, diameter: 20.0 * sx,
+            color: color::RED,
+            transform: Some(Affine2::identity().rotate_around((x, y), -rotation).into()),
+            .. Default::default()
+        }).unwrap();
+    }
+}
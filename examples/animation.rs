@@ -0,0 +1,54 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::{Animation, AnimatedSprite, AnimationEvent, PlayMode};
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let sheet = lux.load_texture_file("./test/minecraft_fixedwidth_font.png")
+                   .unwrap()
+                   .into_sprite()
+                   .as_uniform_sprite_sheet(16, 16);
+
+    let mut sprite = AnimatedSprite::new();
+    // The digits 0 to 3 bounce back and forth.
+    sprite.add_clip("idle", Animation::from_uniform_sheet(
+        &sheet, &[(0, 3), (1, 3), (2, 3), (3, 3)], 0.25, PlayMode::PingPong));
+    // A countdown that plays once, holding the last digit a little longer.
+    sprite.add_clip("launch", Animation::new(PlayMode::Once)
+        .with_frame(sheet.get(3, 3), 0.5)
+        .with_frame(sheet.get(2, 3), 0.5)
+        .with_frame(sheet.get(1, 3), 0.5)
+        .with_frame(sheet.get(0, 3), 1.0));
+    sprite.play("idle");
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        if lux.is_key_pressed(' ') {
+            sprite.play("launch");
+        }
+
+        // With vsync on, every frame is about a sixtieth of a second.  In a
+        // `Game`, pass the `dt` from `update` instead.
+        sprite.update(1.0 / 60.0);
+
+        for event in sprite.events() {
+            match event {
+                AnimationEvent::Looped(name) => println!("{} looped", name),
+                AnimationEvent::Finished(name) => println!("{} finished", name),
+            }
+        }
+
+        // Go back to idling once the launch is over.
+        if sprite.is_finished() {
+            sprite.play("idle");
+        }
+
+        frame.draw(Picture {
+            size: Some((128.0, 128.0)),
+            .. sprite.picture(50.0, 50.0)
+        }).unwrap();
+    }
+}
@@ -0,0 +1,63 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::{TextureAtlas, AtlasOptions};
+use lux::interactive::Event;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut atlas = TextureAtlas::new(AtlasOptions {
+        page_size: (512, 512),
+        padding: 2,
+    });
+
+    // Both images end up on the same page, so every picture below is drawn
+    // with one texture.
+    let logo = atlas.load_file(&lux, "./test/test.png").unwrap();
+    let font = atlas.load_file(&lux, "./test/minecraft_fixedwidth_font.png").unwrap();
+    let letters = font.as_uniform_sprite_sheet(16, 16);
+    let mut dropped = vec![];
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (x, y) = lux.mouse_pos();
+
+        for i in 0 .. 10 {
+            let offset = i as f32 * 40.0;
+            frame.draw(Picture {
+                sprite: Some(&logo),
+                x: x + offset, y: y,
+                size: Some((32.0, 32.0)),
+                .. Default::default()
+            }).unwrap();
+
+            frame.draw(Picture {
+                sprite: Some(&letters.get(i + 1, 4)),
+                x: x + offset, y: y + 40.0,
+                size: Some((32.0, 32.0)),
+                .. Default::default()
+            }).unwrap();
+        }
+
+        for (i, sprite) in dropped.iter().enumerate() {
+            frame.draw(Picture {
+                sprite: Some(sprite),
+                x: i as f32 * 70.0, y: 0.0,
+                size: Some((64.0, 64.0)),
+                .. Default::default()
+            }).unwrap();
+        }
+
+        // Images can be added at any time; drop some onto the window.
+        for event in lux.events() {
+            if let Event::FileDropped(path) = event {
+                match atlas.load_file(&lux, &path) {
+                    Ok(sprite) => dropped.push(sprite),
+                    Err(e) => println!("{}", e),
+                }
+                println!("pages: {}", atlas.num_pages());
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+
+    // Load faces that were rasterized ahead of time.
+    for &size in &[12, 20, 30] {
+        lux.load_baked_font("SourceCodePro", size,
+                            &format!("./resources/SourceCodePro-Regular-{}.bincode", size),
+                            &format!("./resources/SourceCodePro-Regular-{}.png", size)).unwrap();
+    }
+
+    // Bake a face of our own so that it can be loaded the same way next time.
+    lux.load_font("Pacifico", "./resources/Pacifico.ttf").unwrap();
+    lux.bake_font("Pacifico", 40, "./target/Pacifico-40.bincode", "./target/Pacifico-40.png").unwrap();
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let mut y = 10.0;
+        for &size in &[12, 20, 30] {
+            frame.draw(Text {
+                text: "The quick brown fox jumps over the lazy dog",
+                x: 10.0, y: y,
+                size: size,
+                .. Default::default()
+            }).unwrap();
+            y += size as f32 * 1.5;
+        }
+    }
+}
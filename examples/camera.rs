@@ -0,0 +1,69 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::Camera2D;
+
+const WORLD: (f32, f32, f32, f32) = (0.0, 0.0, 2000.0, 1200.0);
+const SPEED: f32 = 5.0;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut camera = Camera2D::new(lux.width(), lux.height());
+    let mut player = (100.0, 100.0);
+
+    while lux.is_open() {
+        // wasd moves the player, q and e zoom, z and x rotate.
+        if lux.is_key_pressed('a') { player.0 -= SPEED; }
+        if lux.is_key_pressed('d') { player.0 += SPEED; }
+        if lux.is_key_pressed('w') { player.1 -= SPEED; }
+        if lux.is_key_pressed('s') { player.1 += SPEED; }
+        if lux.is_key_pressed('q') { camera.zoom *= 0.98; }
+        if lux.is_key_pressed('e') { camera.zoom *= 1.02; }
+        if lux.is_key_pressed('z') { camera.rotation -= 0.02; }
+        if lux.is_key_pressed('x') { camera.rotation += 0.02; }
+
+        // Keep the player inside of a box in the middle of the screen, and
+        // never show anything outside of the world.
+        camera.viewport = (0.0, 0.0, lux.width(), lux.height());
+        camera.follow_smoothly(player, (200.0, 100.0), 5.0, 1.0 / 60.0);
+        camera.clamp_to(WORLD);
+
+        let mouse = camera.screen_to_world(lux.mouse_pos());
+
+        let mut frame = lux.cleared_frame(color::GRAY);
+        camera.with_view(&mut frame, |frame| {
+            frame.draw(Rectangle {
+                x: WORLD.0, y: WORLD.1, w: WORLD.2, h: WORLD.3,
+                color: color::WHITE,
+                .. Default::default()
+            }).unwrap();
+
+            // A checkerboard to show off the camera movement.
+            for i in 0 .. 20 {
+                for j in 0 .. 12 {
+                    if (i + j) % 2 == 0 {
+                        frame.draw(Square {
+                            x: i as f32 * 100.0, y: j as f32 * 100.0, size: 100.0,
+                            color: color::LIGHTGREY,
+                            .. Default::default()
+                        }).unwrap();
+                    }
+                }
+            }
+
+            frame.draw(Square {
+                x: player.0 - 10.0, y: player.1 - 10.0, size: 20.0,
+                color: color::BLUE,
+                .. Default::default()
+            }).unwrap();
+
+            // The mouse position in world coordinates.
+            frame.draw(Circle {
+                x: mouse.0 - 5.0, y: mouse.1 - 5.0, diameter: 10.0,
+                color: color::RED,
+                .. Default::default()
+            }).unwrap();
+        });
+    }
+}
@@ -0,0 +1,62 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut window = Window::new_with_defaults().unwrap();
+    let ants = Dash::new(&[6.0, 4.0], 0.0);
+    let dots = Dash::new(&[0.0, 12.0], 0.0);
+    let mut offset = 0.0;
+    let mut start = None;
+
+    while window.is_open() {
+        let mut frame = window.cleared_frame(color::WHITE);
+        let (mx, my) = window.mouse_pos();
+
+        // Drag with the mouse to draw a selection rectangle.
+        if window.is_mouse_down() {
+            if start.is_none() {
+                start = Some((mx, my));
+            }
+        } else {
+            start = None;
+        }
+
+        if let Some((sx, sy)) = start {
+            frame.draw(Rectangle {
+                x: sx.min(mx), y: sy.min(my),
+                w: (mx - sx).abs(), h: (my - sy).abs(),
+                style: DrawStyle::Stroke,
+                dash: Some(ants.with_offset(offset)),
+                .. Default::default()
+            }).unwrap();
+        }
+
+        // The pattern continues around corners and scales with the matrix.
+        let route = [(50.0, 400.0), (150.0, 300.0), (250.0, 450.0), (350.0, 350.0)];
+        frame.with_scale(1.5, 1.5, |frame| {
+            frame.draw(Polyline {
+                points: &route[..],
+                thickness: 4.0,
+                color: color::BLUE,
+                join: LineJoin::Round,
+                cap: LineCap::Round,
+                dash: Some(dots.with_offset(-offset)),
+                .. Default::default()
+            }).unwrap();
+        });
+
+        frame.draw(Circle {
+            x: 500.0, y: 50.0,
+            diameter: 100.0,
+            style: DrawStyle::Stroke,
+            stroke_width: 3.0,
+            stroke_color: color::RED,
+            dash: Some(Dash::new(&[15.0, 5.0, 5.0, 5.0], offset)),
+            .. Default::default()
+        }).unwrap();
+
+        offset += 0.5;
+    }
+}
@@ -0,0 +1,64 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut window = Window::new_with_defaults().unwrap();
+    let mut health: f32 = 1.0;
+
+    let sky = Fill::linear((0.0, 0.0), (0.0, 600.0), &[
+        (0.0, rgb(0.1, 0.2, 0.5)),
+        (0.7, rgb(0.9, 0.6, 0.4)),
+        (1.0, rgb(1.0, 0.9, 0.6)),
+    ]);
+
+    while window.is_open() {
+        let mut frame = window.cleared_frame(color::WHITE);
+        let (w, h) = frame.size();
+
+        frame.draw(Rectangle { w: w, h: h, fill: Some(sky), .. Default::default() }).unwrap();
+
+        // A glowing sun that follows the mouse.
+        let (mx, my) = window.mouse_pos();
+        frame.draw(Circle {
+            x: mx - 60.0, y: my - 60.0,
+            diameter: 120.0,
+            fill: Some(Fill::radial((mx, my), 60.0, &[
+                (0.0, rgba(1.0, 1.0, 0.8, 1.0)),
+                (0.5, rgba(1.0, 0.8, 0.2, 0.8)),
+                (1.0, rgba(1.0, 0.5, 0.0, 0.0)),
+            ])),
+            .. Default::default()
+        }).unwrap();
+
+        // A button whose gradient rotates along with it.
+        frame.with_rotate_around((200.0, 150.0), 0.2, |frame| {
+            frame.draw(RoundedRect {
+                x: 100.0, y: 120.0, w: 200.0, h: 60.0,
+                radii: [12.0; 4],
+                fill: Some(Fill::linear((0.0, 120.0), (0.0, 180.0), &[
+                    (0.0, rgb(0.6, 0.8, 1.0)),
+                    (1.0, rgb(0.2, 0.4, 0.8)),
+                ])),
+                style: DrawStyle::FillAndStroke,
+                stroke_color: rgb(0.1, 0.2, 0.5),
+                .. Default::default()
+            }).unwrap();
+        });
+
+        // A health bar that goes from green to red.
+        let bar = Fill::linear((20.0, 0.0), (320.0, 0.0), &[
+            (0.0, color::RED),
+            (0.5, color::YELLOW),
+            (1.0, color::GREEN),
+        ]);
+        frame.draw(Rectangle {
+            x: 20.0, y: 20.0, w: 300.0 * health, h: 20.0,
+            fill: Some(bar),
+            .. Default::default()
+        }).unwrap();
+
+        health = (health - 0.002 + 1.0) % 1.0;
+    }
+}
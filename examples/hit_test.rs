@@ -0,0 +1,51 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+const SIZE: f32 = 150.0;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut angle = 0.0f32;
+
+    while lux.is_open() {
+        angle += 0.01;
+        let mouse = lux.mouse_pos();
+
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (w, h) = frame.size();
+        frame.with_matrix(|frame| {
+            frame.translate(w / 2.0, h / 2.0);
+            frame.rotate(angle);
+            frame.scale(1.0, 0.5);
+
+            // Map the mouse into the square's own coordinates, where the
+            // square is just (-SIZE / 2, -SIZE / 2, SIZE, SIZE).
+            let hovered = match frame.inverse_transform_point(mouse) {
+                Some((x, y)) => x.abs() <= SIZE / 2.0 && y.abs() <= SIZE / 2.0,
+                None => false,
+            };
+
+            // The box on the screen that the square covers.
+            let (bx, by, bw, bh) = frame.transform_rect((-SIZE / 2.0, -SIZE / 2.0, SIZE, SIZE));
+
+            frame.draw(Square {
+                x: -SIZE / 2.0, y: -SIZE / 2.0, size: SIZE,
+                color: if hovered { color::RED } else { color::BLUE },
+                .. Default::default()
+            }).unwrap();
+
+            frame.with_matrix(|frame| {
+                // Drawing in pixels again means undoing the transformations.
+                *frame.current_matrix_mut() = frame.base_matrix();
+                frame.draw(Rectangle {
+                    x: bx, y: by, w: bw, h: bh,
+                    style: DrawStyle::Stroke,
+                    stroke_color: color::GRAY,
+                    .. Default::default()
+                }).unwrap();
+            });
+        });
+    }
+}
@@ -0,0 +1,32 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let panel = lux.load_texture_file("./test/test.png").unwrap().into_sprite();
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (mx, my) = lux.mouse_pos();
+
+        // Resize the panels with the mouse; the 32 pixel borders stay sharp.
+        frame.draw(NineSlice {
+            sprite: Some(&panel),
+            x: 10.0, y: 10.0,
+            w: mx - 10.0, h: my - 10.0,
+            insets: (32, 32, 32, 32),
+            .. Default::default()
+        }).unwrap();
+
+        frame.draw(NineSlice {
+            sprite: Some(&panel),
+            x: mx, y: my,
+            w: 300.0, h: 100.0,
+            insets: (32, 32, 32, 32),
+            tile: true,
+            .. Default::default()
+        }).unwrap();
+    }
+}
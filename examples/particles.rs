@@ -0,0 +1,78 @@
+extern crate lux;
+
+use std::f32::consts::PI;
+
+use lux::prelude::*;
+use lux::game::*;
+use lux::graphics::{ParticleEmitter, EmitterOptions, ParticleShape};
+
+struct Fireworks {
+    fountain: ParticleEmitter,
+    sparks: ParticleEmitter,
+}
+
+impl Game for Fireworks {
+    fn update(&mut self, dt: f32, window: &mut Window, _events: &mut EventIterator) -> LuxResult<()> {
+        self.fountain.set_position(window.width() / 2.0, window.height());
+
+        // Clicking sets off a burst of sparks under the mouse.
+        self.sparks.set_position(window.mouse_x(), window.mouse_y());
+        if window.is_mouse_down() {
+            self.sparks.burst(50);
+        }
+
+        self.fountain.update(dt);
+        self.sparks.update(dt);
+        Ok(())
+    }
+
+    fn render(&mut self, _lag: f32, _window: &mut Window, frame: &mut Frame) -> LuxResult<()> {
+        // Each emitter is a single draw call, no matter how many particles
+        // it has.
+        try!(frame.draw(&self.fountain));
+        try!(frame.draw(&self.sparks));
+        Ok(())
+    }
+
+    fn clear_color(&self) -> Option<[f32; 4]> {
+        Some(rgb(0.0, 0.0, 0.1))
+    }
+
+    fn show_fps(&self, window: &Window) -> bool {
+        window.is_key_pressed(' ')
+    }
+}
+
+fn main() {
+    let fountain = ParticleEmitter::new(EmitterOptions {
+        rate: 2000.0,
+        max_particles: 10000,
+        lifetime: (2.0, 3.0),
+        speed: (400.0, 600.0),
+        // Straight up, give or take a little.
+        direction: (-PI / 2.0 - 0.2, -PI / 2.0 + 0.2),
+        gravity: (0.0, 400.0),
+        spawn_area: (40.0, 0.0),
+        colors: vec![rgb(0.6, 0.8, 1.0), rgb(0.2, 0.4, 1.0), rgba(0.0, 0.0, 1.0, 0.0)],
+        sizes: vec![3.0, 6.0],
+        rotation: (0.0, PI),
+        spin: (-5.0, 5.0),
+        .. Default::default()
+    });
+
+    let mut sparks = ParticleEmitter::new(EmitterOptions {
+        max_particles: 5000,
+        lifetime: (0.5, 1.5),
+        speed: (50.0, 300.0),
+        gravity: (0.0, 200.0),
+        drag: 1.5,
+        colors: vec![rgb(1.0, 1.0, 0.5), rgb(1.0, 0.5, 0.0), rgba(1.0, 0.0, 0.0, 0.0)],
+        sizes: vec![8.0, 2.0],
+        shape: ParticleShape::Circle(8),
+        .. Default::default()
+    });
+    // Sparks only appear in bursts.
+    sparks.emitting = false;
+
+    Fireworks { fountain: fountain, sparks: sparks }.run_until_end().unwrap();
+}
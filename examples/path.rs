@@ -0,0 +1,53 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::Path;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+
+    // A heart made out of two cubic curves.
+    let mut heart = Path::new();
+    heart.move_to(0.0, 30.0)
+         .cubic_to(-80.0, -30.0, -30.0, -90.0, 0.0, -40.0)
+         .cubic_to(30.0, -90.0, 80.0, -30.0, 0.0, 30.0)
+         .close();
+
+    // A speech bubble with rounded corners and a tail.
+    let mut bubble = Path::new();
+    bubble.move_to(40.0, 0.0)
+          .arc_to(200.0, 0.0, 200.0, 100.0, 20.0)
+          .arc_to(200.0, 100.0, 0.0, 100.0, 20.0)
+          .line_to(70.0, 100.0)
+          .quad_to(40.0, 140.0, 20.0, 140.0)
+          .quad_to(40.0, 120.0, 40.0, 100.0)
+          .arc_to(0.0, 100.0, 0.0, 0.0, 20.0)
+          .arc_to(0.0, 0.0, 200.0, 0.0, 20.0)
+          .close();
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (x, y) = lux.mouse_pos();
+
+        frame.with_translate(x, y, |frame| {
+            frame.draw(PathShape {
+                path: Some(&heart),
+                color: color::RED,
+                style: DrawStyle::FillAndStroke,
+                stroke_width: 3.0,
+                .. Default::default()
+            }).unwrap();
+        });
+
+        frame.with_translate(100.0, 100.0, |frame| {
+            frame.draw(PathShape {
+                path: Some(&bubble),
+                color: rgb(0.8, 0.9, 1.0),
+                style: DrawStyle::FillAndStroke,
+                stroke_width: 2.0,
+                .. Default::default()
+            }).unwrap();
+        });
+    }
+}
@@ -0,0 +1,48 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+
+    // Pictures of this sprite are centered on their position by default.
+    let sprite = lux.load_texture_file("./test/test.png").unwrap()
+                    .into_sprite()
+                    .with_pivot((0.5, 0.5));
+    let mut angle = 0.0f32;
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (x, y) = lux.mouse_pos();
+        angle += 0.02;
+
+        // Spins around its center.
+        frame.draw(Picture {
+            sprite: Some(&sprite),
+            x: 150.0, y: 150.0,
+            rotation: angle,
+            .. Default::default()
+        }).unwrap();
+
+        // Faces the mouse, mirrored when the mouse is on the left.
+        frame.draw(Picture {
+            sprite: Some(&sprite),
+            x: 450.0, y: 150.0,
+            scale: (0.5, 0.5),
+            flip_x: x < 450.0,
+            .. Default::default()
+        }).unwrap();
+
+        // Hangs from its top center, swinging back and forth.
+        frame.draw(Picture {
+            sprite: Some(&sprite),
+            x: x, y: y,
+            size: Some((64.0, 64.0)),
+            origin: Some((0.5, 0.0)),
+            rotation: angle.sin() * 0.5,
+            flip_y: true,
+            .. Default::default()
+        }).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn star(cx: f32, cy: f32, r: f32) -> Vec<(f32, f32)> {
+    (0 .. 5).map(|i| {
+        let theta = (i * 2) as f32 * 2.0 * std::f32::consts::PI / 5.0 - std::f32::consts::PI / 2.0;
+        (cx + theta.cos() * r, cy + theta.sin() * r)
+    }).collect()
+}
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+
+    let frame_outline = [(300.0, 50.0), (550.0, 50.0), (550.0, 150.0), (425.0, 250.0), (300.0, 150.0)];
+    let window_a = [(330.0, 80.0), (410.0, 80.0), (410.0, 140.0), (330.0, 140.0)];
+    let window_b = [(440.0, 80.0), (520.0, 80.0), (520.0, 140.0), (440.0, 140.0)];
+    let holes = [&window_a[..], &window_b[..]];
+
+    let left = star(125.0, 150.0, 100.0);
+    let right = star(125.0, 400.0, 100.0);
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        // The same self-intersecting star with both fill rules.
+        frame.draw(Polygon {
+            points: &left[..],
+            fill_rule: FillRule::NonZero,
+            color: color::BLUE,
+            .. Default::default()
+        }).unwrap();
+        frame.draw(Polygon {
+            points: &right[..],
+            fill_rule: FillRule::EvenOdd,
+            color: color::BLUE,
+            .. Default::default()
+        }).unwrap();
+
+        // A concave outline with two holes cut out of it.
+        frame.draw(Polygon {
+            points: &frame_outline[..],
+            holes: &holes[..],
+            color: color::GREEN,
+            style: DrawStyle::FillAndStroke,
+            stroke_width: 2.0,
+            .. Default::default()
+        }).unwrap();
+    }
+}
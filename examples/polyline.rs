@@ -0,0 +1,44 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut window = Window::new_with_defaults().unwrap();
+
+    let joins = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+    let caps = [LineCap::Butt, LineCap::Square, LineCap::Round];
+
+    while window.is_open() {
+        let mut frame = window.cleared_frame(color::WHITE);
+        let mouse = window.mouse_pos();
+
+        // Every combination of join and cap, bending towards the mouse.
+        for (i, &join) in joins.iter().enumerate() {
+            for (j, &cap) in caps.iter().enumerate() {
+                let (x, y) = (100.0 + j as f32 * 200.0, 100.0 + i as f32 * 150.0);
+                let points = [(x - 60.0, y + 40.0),
+                              (x + (mouse.0 - x) / 10.0, y + (mouse.1 - y) / 10.0 - 40.0),
+                              (x + 60.0, y + 40.0)];
+                frame.draw(Polyline {
+                    points: &points[..],
+                    thickness: 20.0,
+                    color: rgba(0.0, 0.0, 1.0, 0.5),
+                    join: join,
+                    cap: cap,
+                    .. Default::default()
+                }).unwrap();
+            }
+        }
+
+        // A closed loop that meets itself without a seam.
+        let triangle = [(650.0, 450.0), (750.0, 450.0), (700.0, 370.0)];
+        frame.draw(Polyline {
+            points: &triangle[..],
+            closed: true,
+            thickness: 10.0,
+            color: rgba(1.0, 0.0, 0.0, 0.5),
+            .. Default::default()
+        }).unwrap();
+    }
+}
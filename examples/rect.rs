@@ -15,6 +15,6 @@ fn main() {
         // Specify the color and transformation matrix
         let mut matrix = vecmath::mat4_id();
         matrix.rotate_around((50.0, 50.0), 1.25);
-        frame.draw(Rectangle { x: 0.0, y: 0.0, w: 100.0, h: 300.0, color: rgb(0.0, 1.0, 0.0), transform: Some(matrix)}).unwrap();
+        frame.draw(Rectangle { x: 0.0, y: 0.0, w: 100.0, h: 300.0, color: rgb(0.0, 1.0, 0.0), transform: Some(matrix), .. Default::default()}).unwrap();
     }
 }
@@ -17,9 +17,23 @@ fn main() {
                 Square {
                     x: pos, y: 50.0,
                     size: 50.0,
+                    style: DrawStyle::FillAndStroke,
+                    stroke_width: i as f32 * 5.0,
+                    stroke_color: rgba(0.0, 0.0, 1.0, 0.4),
                     transform: Some(*mat4_id().rotate_around((50.0, 50.0), rot)),
                     .. Default::default()
                 }).unwrap();
         }
+
+        // Outlines keep the same width along each side of a hexagon.
+        frame.draw(
+            Circle {
+                x: 50.0, y: 150.0,
+                diameter: 100.0,
+                segments: Some(6),
+                style: DrawStyle::Stroke,
+                stroke_width: 8.0,
+                .. Default::default()
+            }).unwrap();
     }
 }
@@ -0,0 +1,66 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+use std::f32::consts::PI;
+
+fn main() {
+    let mut window = Window::new_with_defaults().unwrap();
+    let mut t: f32 = 0.0;
+
+    while window.is_open() {
+        let mut frame = window.cleared_frame(color::WHITE);
+
+        // A panel with different radii on each corner.
+        frame.draw(RoundedRect {
+            x: 20.0, y: 20.0,
+            w: 300.0, h: 150.0,
+            radii: [30.0, 10.0, 30.0, 0.0],
+            color: rgb(0.85, 0.9, 1.0),
+            style: DrawStyle::FillAndStroke,
+            stroke_width: 2.0,
+            stroke_color: color::STEELBLUE,
+            .. Default::default()
+        }).unwrap();
+
+        // A cooldown timer that fills up over time.
+        let progress = (t % 1.0) * 2.0 * PI;
+        frame.draw(Pie {
+            x: 450.0, y: 95.0,
+            radius: 60.0,
+            start_angle: -PI / 2.0,
+            end_angle: -PI / 2.0 + progress,
+            color: rgba(0.0, 0.0, 0.0, 0.5),
+            .. Default::default()
+        }).unwrap();
+
+        // A health ring with a background track.
+        frame.draw(Ring {
+            x: 150.0, y: 350.0,
+            inner_radius: 60.0, outer_radius: 80.0,
+            color: color::LIGHTGRAY,
+            .. Default::default()
+        }).unwrap();
+        frame.draw(Ring {
+            x: 150.0, y: 350.0,
+            inner_radius: 60.0, outer_radius: 80.0,
+            start_angle: -PI / 2.0,
+            end_angle: -PI / 2.0 + 1.5 * PI * (0.5 + 0.5 * t.sin()),
+            color: color::RED,
+            .. Default::default()
+        }).unwrap();
+
+        frame.draw(Arc {
+            x: 450.0, y: 350.0,
+            radius: 70.0,
+            start_angle: t, end_angle: t + PI,
+            thickness: 8.0,
+            cap: LineCap::Round,
+            color: color::GREEN,
+            .. Default::default()
+        }).unwrap();
+
+        t += 0.01;
+    }
+}
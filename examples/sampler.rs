@@ -0,0 +1,43 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::{Sampler, Wrap, TexVertex, PrimitiveCanvas, TrianglesList};
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let smooth = lux.load_texture_file("./test/test.png").unwrap().into_sprite();
+    let sharp = smooth.with_sampler(Sampler::nearest());
+    let tiled = smooth.with_sampler(Sampler { wrap: Wrap::Repeat, .. Sampler::nearest() });
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        // The same texture blown up with linear and nearest filtering.
+        let region = smooth.sub_sprite((0, 0), (16, 16)).unwrap();
+        frame.draw(Picture {
+            sprite: Some(&region),
+            x: 10.0, y: 10.0,
+            size: Some((256.0, 256.0)),
+            .. Default::default()
+        }).unwrap();
+
+        let region = sharp.sub_sprite((0, 0), (16, 16)).unwrap();
+        frame.draw(Picture {
+            sprite: Some(&region),
+            x: 276.0, y: 10.0,
+            size: Some((256.0, 256.0)),
+            .. Default::default()
+        }).unwrap();
+
+        // Texture coordinates past 1.0 repeat the texture.
+        let tex_vs = [
+            TexVertex { pos: [10.0, 276.0], tex_coords: [0.0, 0.0], color: [1.0, 1.0, 1.0, 1.0] },
+            TexVertex { pos: [522.0, 276.0], tex_coords: [4.0, 0.0], color: [1.0, 1.0, 1.0, 1.0] },
+            TexVertex { pos: [522.0, 404.0], tex_coords: [4.0, 1.0], color: [1.0, 1.0, 1.0, 1.0] },
+            TexVertex { pos: [10.0, 404.0], tex_coords: [0.0, 1.0], color: [1.0, 1.0, 1.0, 1.0] },
+        ];
+        frame.draw_tex(TrianglesList, &tex_vs[..], Some(&[0, 1, 2, 0, 2, 3][..]), None,
+                       tiled.texture(), None, Some(tiled.sampler())).unwrap();
+    }
+}
@@ -0,0 +1,63 @@
+extern crate lux;
+
+use std::path::Path;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::SheetData;
+
+// The kind of file that Aseprite exports with the "array" layout.  Normally
+// it would be loaded along with its image by `load_sheet_json`.
+const SHEET: &'static str = r#"{
+    "frames": [
+        { "filename": "walk 0", "frame": { "x": 0, "y": 0, "w": 128, "h": 128 },
+          "rotated": false, "trimmed": false,
+          "spriteSourceSize": { "x": 0, "y": 0, "w": 128, "h": 128 },
+          "sourceSize": { "w": 128, "h": 128 }, "duration": 200 },
+        { "filename": "walk 1", "frame": { "x": 128, "y": 0, "w": 96, "h": 112 },
+          "rotated": false, "trimmed": true,
+          "spriteSourceSize": { "x": 16, "y": 16, "w": 96, "h": 112 },
+          "sourceSize": { "w": 128, "h": 128 }, "duration": 100 },
+        { "filename": "walk 2", "frame": { "x": 0, "y": 128, "w": 127, "h": 64 },
+          "rotated": true, "trimmed": false,
+          "spriteSourceSize": { "x": 0, "y": 0, "w": 127, "h": 64 },
+          "sourceSize": { "w": 127, "h": 64 }, "pivot": { "x": 0.5, "y": 1.0 },
+          "duration": 300 }
+    ],
+    "meta": {
+        "image": "test.png",
+        "frameTags": [ { "name": "walk", "from": 0, "to": 2, "direction": "pingpong" } ]
+    }
+}"#;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let data = SheetData::from_json(SHEET).unwrap();
+    let image = Path::new("./test/test.png").into_sprite(&lux).unwrap();
+    let sheet = data.sprite_sheet(&image).unwrap();
+    let walk = data.tag_frames(data.tag("walk").unwrap());
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        for (i, f) in walk.iter().enumerate() {
+            let x = 100.0 + i as f32 * 200.0;
+
+            // Every frame lines up on the same spot, whether it was trimmed
+            // or rotated on the sheet.
+            frame.draw(Picture {
+                sprite: Some(&sheet.get(&f.name[..])),
+                x: x, y: 200.0,
+                rotation: f.rotation(),
+                .. Default::default()
+            }).unwrap();
+
+            frame.draw(Circle {
+                x: x - 3.0, y: 197.0,
+                diameter: 6.0,
+                color: color::RED,
+                .. Default::default()
+            }).unwrap();
+        }
+    }
+}
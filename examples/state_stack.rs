@@ -0,0 +1,63 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+struct Panel {
+    x: f32,
+    y: f32,
+    spin: f32,
+    items_drawn: u32,
+}
+
+impl Panel {
+    fn render(&mut self, frame: &mut Frame) -> LuxResult<()> {
+        // No closures, so `self` can be changed freely while drawing.
+        frame.push_state();
+        frame.translate(self.x, self.y);
+        frame.set_scissor(Some((self.x as u32, self.y as u32, 200, 200)));
+
+        try!(frame.draw(Rectangle {
+            w: 200.0, h: 200.0,
+            color: color::LIGHTGREY,
+            .. Default::default()
+        }));
+
+        for i in 0 .. 4 {
+            // The guard pops the rotation when it goes out of scope.
+            let mut frame = frame.state_guard();
+            frame.translate(50.0 + (i % 2) as f32 * 100.0, 50.0 + (i / 2) as f32 * 100.0);
+            frame.rotate(self.spin * (i + 1) as f32);
+            try!(frame.draw(Square {
+                x: -60.0, y: -60.0, size: 120.0,
+                color: if i % 2 == 0 { color::RED } else { color::BLUE },
+                .. Default::default()
+            }));
+            self.items_drawn += 1;
+        }
+
+        frame.pop_state()
+    }
+}
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut panels = vec![
+        Panel { x: 50.0, y: 50.0, spin: 0.0, items_drawn: 0 },
+        Panel { x: 300.0, y: 100.0, spin: 0.0, items_drawn: 0 },
+    ];
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        for panel in &mut panels {
+            panel.spin += 0.01;
+            panel.render(&mut frame).unwrap();
+        }
+
+        // Every push was matched by a pop, so there is nothing left to pop.
+        assert_eq!(frame.state_depth(), 0);
+        assert!(frame.pop_state().is_err());
+    }
+
+    println!("Drew {} items", panels.iter().map(|p| p.items_drawn).sum::<u32>());
+}
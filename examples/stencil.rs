@@ -35,8 +35,9 @@ fn draw_field(
                 x: x, y: y,
                 size: SIZE,
                 color: color,
-                transform: Some(*mat4_id().rotate_around((x + SIZE / 2.0, y + SIZE / 2.0), theta))
-            });
+                transform: Some(*mat4_id().rotate_around((x + SIZE / 2.0, y + SIZE / 2.0), theta)),
+                .. Default::default()
+            }).unwrap();
         }
     }
 }
@@ -0,0 +1,45 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+use lux::font::{HorizontalAlign, VerticalAlign};
+
+const MESSAGE: &'static str = "The quick brown fox jumps over the lazy dog.\nPack my box with five dozen liquor jugs.";
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    lux.load_font("SourceCodePro", "./resources/SourceCodePro-Regular.ttf").unwrap();
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        let (mx, my) = lux.mouse_pos();
+
+        // Wrap the text to the distance between the mouse and the center.
+        let text = Text {
+            text: MESSAGE,
+            x: 400.0, y: 250.0,
+            max_width: Some((mx - 400.0).abs().max(50.0) * 2.0),
+            align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Middle,
+            .. Default::default()
+        };
+
+        // Size a panel to fit the text before drawing it.
+        let (w, h) = lux.measure_text(&text).unwrap();
+        frame.draw(Rectangle {
+            x: 400.0 - w / 2.0, y: 250.0 - h / 2.0,
+            w: w, h: h,
+            color: rgb(0.8, 0.8, 0.9),
+            .. Default::default()
+        }).unwrap();
+        frame.draw(text).unwrap();
+
+        frame.draw(Text {
+            text: "bottom right",
+            x: mx, y: my,
+            align: HorizontalAlign::Right,
+            vertical_align: VerticalAlign::Bottom,
+            .. Default::default()
+        }).unwrap();
+    }
+}
@@ -0,0 +1,42 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let whole = lux.load_texture_file("./test/test.png").unwrap().into_sprite();
+    let sheet = lux.load_texture_file("./test/minecraft_fixedwidth_font.png")
+                   .unwrap()
+                   .into_sprite()
+                   .as_uniform_sprite_sheet(16, 16);
+    let cell = sheet.get(1, 4);
+    let mut t = 0.0f32;
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+        t += 0.01;
+
+        // A whole texture, scrolling to the right.
+        frame.draw(TiledPicture {
+            sprite: Some(&whole),
+            x: 10.0, y: 10.0,
+            w: 500.0, h: 200.0,
+            tile_size: Some((64.0, 64.0)),
+            uv_offset: (-t, 0.0),
+            .. Default::default()
+        }).unwrap();
+
+        // One cell of a sprite sheet, scrolling diagonally and zooming.
+        frame.draw(TiledPicture {
+            sprite: Some(&cell),
+            x: 10.0, y: 220.0,
+            w: 500.0, h: 200.0,
+            tile_size: Some((32.0, 32.0)),
+            uv_offset: (t, t * 0.5),
+            uv_scale: (1.5 + t.sin() * 0.5, 1.5 + t.sin() * 0.5),
+            color: color::rgb(0.2, 0.4, 0.8),
+            .. Default::default()
+        }).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+extern crate lux;
+
+use std::path::Path;
+
+use lux::prelude::*;
+use lux::color;
+use lux::graphics::{TileMap, MapView, Layer, ObjectShape};
+
+/// Builds a TMX map that uses the font texture as its tileset.  Maps made in
+/// Tiled can be loaded straight from disk with `load_tilemap`.
+fn map_source(width: u32, height: u32) -> String {
+    let mut ground = vec![];
+    let mut letters = vec![];
+    for y in 0 .. height {
+        for x in 0 .. width {
+            // '#' for the border, '.' everywhere else.
+            let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            let tile = if edge { 1 + 35 } else { 1 + 46 };
+            ground.push(tile.to_string());
+
+            // Every other row of letters is mirrored.
+            let letter = if y % 4 == 2 && x % 3 == 1 { 1 + 65 + (x / 3) % 26 } else { 0 };
+            let flip = if letter != 0 && y % 8 == 6 { 0x80000000u32 } else { 0 };
+            letters.push((letter | flip).to_string());
+        }
+    }
+
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="{w}" height="{h}" tilewidth="32" tileheight="32" infinite="0">
+ <tileset firstgid="1" name="font" tilewidth="32" tileheight="32" tilecount="256" columns="16">
+  <image source="minecraft_fixedwidth_font.png" width="512" height="512"/>
+ </tileset>
+ <layer id="1" name="ground" width="{w}" height="{h}">
+  <data encoding="csv">{ground}</data>
+ </layer>
+ <layer id="2" name="letters" width="{w}" height="{h}" opacity="0.6">
+  <data encoding="csv">{letters}</data>
+ </layer>
+ <objectgroup id="3" name="markers">
+  <object id="1" name="spawn" x="96" y="96"><point/></object>
+ </objectgroup>
+</map>"#, w = width, h = height, ground = ground.join(","), letters = letters.join(","))
+}
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let mut map = TileMap::from_tmx(&map_source(100, 100)).unwrap();
+    // Loading from a path keeps the rows of the image in the order that
+    // Tiled counts tiles in.
+    let sprite = Path::new("./test/minecraft_fixedwidth_font.png").into_sprite(&lux).unwrap();
+    map.set_tileset_sprite(0, sprite);
+
+    let spawn = match map.layers.iter().find(|l| l.name() == "markers") {
+        Some(&Layer::Objects(ref layer)) => {
+            layer.objects.iter().find(|o| o.shape == ObjectShape::Point).map(|o| (o.x, o.y))
+        }
+        _ => None,
+    }.unwrap_or((0.0, 0.0));
+
+    let (map_w, map_h) = map.pixel_size();
+    while lux.is_open() {
+        // Moving the mouse across the window scrolls across the whole map.
+        let (mx, my) = lux.mouse_pos();
+        let mut frame = lux.cleared_frame(color::BLACK);
+        let (win_w, win_h) = frame.size();
+        let scroll_x = spawn.0 + (mx / win_w) * (map_w - win_w - spawn.0);
+        let scroll_y = spawn.1 + (my / win_h) * (map_h - win_h - spawn.1);
+
+        // Only the chunks that are on screen get drawn, and each chunk is
+        // only built once.
+        frame.draw(MapView {
+            map: Some(&map),
+            x: -scroll_x,
+            y: -scroll_y,
+            view: Some((scroll_x, scroll_y, win_w, win_h)),
+            .. Default::default()
+        }).unwrap();
+    }
+}
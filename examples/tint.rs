@@ -0,0 +1,27 @@
+extern crate lux;
+
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut lux = Window::new_with_defaults().unwrap();
+    let sprite = lux.load_texture_file("./test/test.png").unwrap().into_sprite();
+
+    while lux.is_open() {
+        let mut frame = lux.cleared_frame(color::WHITE);
+
+        // Every picture has a different tint and fade, but they all share
+        // one texture so they are drawn in a single batch.
+        for i in 0 .. 100 {
+            let t = i as f32 / 100.0;
+            frame.draw(Picture {
+                sprite: Some(&sprite),
+                x: (i % 10) as f32 * 50.0,
+                y: (i / 10) as f32 * 50.0,
+                size: Some((48.0, 48.0)),
+                color: hsva(t * 360.0, 1.0, 1.0, 1.0 - t * 0.8),
+                .. Default::default()
+            }).unwrap();
+        }
+    }
+}
@@ -0,0 +1,68 @@
+extern crate lux;
+
+use lux::window::{WindowOptions, VirtualResolution, ScaleMode};
+use lux::interactive::Event;
+use lux::prelude::*;
+use lux::color;
+
+fn main() {
+    let mut window = Window::new(WindowOptions {
+        dimensions: (1000, 600),
+        title: "virtual resolution".to_owned(),
+        virtual_resolution: Some(VirtualResolution {
+            size: (320, 180),
+            mode: ScaleMode::Integer,
+        }),
+        .. Default::default()
+    }).unwrap();
+
+    while window.is_open() {
+        // Space cycles through the scale modes.  Resize the window to see
+        // how each of them fits the picture.
+        for event in window.events() {
+            match event {
+                Event::KeyPressed(_, Some(' '), _) => {
+                    window.change_options(|opts| {
+                        if let Some(ref mut v) = opts.virtual_resolution {
+                            v.mode = match v.mode {
+                                ScaleMode::Integer => ScaleMode::Letterbox,
+                                ScaleMode::Letterbox => ScaleMode::Stretch,
+                                ScaleMode::Stretch => ScaleMode::Integer,
+                            };
+                        }
+                    }).unwrap();
+                }
+                Event::WindowResized(size) => println!("window resized to {:?}", size),
+                _ => {}
+            }
+        }
+
+        // The mouse position is already in the 320x180 space.
+        let (mx, my) = window.mouse_pos_i();
+
+        let mut frame = window.cleared_frame(color::BLACK);
+        let (w, h) = frame.size();
+        frame.draw(Rectangle {
+            x: 0.0, y: 0.0, w: w, h: h,
+            color: color::WHITE,
+            .. Default::default()
+        }).unwrap();
+
+        // A checkerboard of single logical pixels along the top edge.
+        for i in 0 .. w as i32 {
+            if i % 2 == 0 {
+                frame.draw(Square {
+                    x: i as f32, y: 0.0, size: 1.0,
+                    color: color::GRAY,
+                    .. Default::default()
+                }).unwrap();
+            }
+        }
+
+        frame.draw(Square {
+            x: mx as f32 - 4.0, y: my as f32 - 4.0, size: 8.0,
+            color: color::RED,
+            .. Default::default()
+        }).unwrap();
+    }
+}
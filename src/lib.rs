@@ -12,6 +12,8 @@ extern crate num;
 extern crate clock_ticks;
 extern crate poison_pool;
 extern crate font_atlas;
+extern crate rustc_serialize;
+extern crate bincode;
 
 mod private;
 
@@ -36,9 +38,14 @@ pub mod graphics {
     //! `DrawableTexture`.
 
     //pub use private::canvas::{Canvas, Rectangle, Ellipse, ContainedSprite};
-    pub use private::canvas::{Canvas, Rectangle, Square, Ellipse, Circle, Picture, Line, Pixels};
-    pub use private::gfx_integration::{ColorVertex, TexVertex};
+    pub use private::canvas::{Canvas, Rectangle, Square, Ellipse, Circle, Picture, Line, Pixels, Text, DrawStyle, Polygon, Polyline,
+                              RoundedRect, Arc, Pie, Ring, NineSlice, TiledPicture};
+    pub use private::tessellate::{FillRule, LineJoin, LineCap, Dash};
+    pub use private::fill::{Fill, Gradient};
+    pub use private::path::{Path, PathCommand, PathShape, Contour};
+    pub use private::gfx_integration::{ColorVertex, TexVertex, Sampler, Filter, Wrap};
     pub use private::primitive_canvas::{PrimitiveCanvas, StencilType};
+    pub use private::canvas::StateGuard;
     pub use private::sprite::{
         IntoSprite,
         Sprite,
@@ -48,6 +55,13 @@ pub mod graphics {
         NonUniformSpriteSheet,
         TextureLoader
     };
+    pub use private::atlas::{TextureAtlas, AtlasOptions};
+    pub use private::sheet_json::{SheetData, SheetFrame, FrameTag, TagDirection, load_sheet_json};
+    pub use private::animation::{Animation, AnimatedSprite, AnimationEvent, PlayMode};
+    pub use private::tilemap::{TileMap, Tileset, Tile, Layer, TileLayer, ObjectLayer, MapObject, ObjectShape,
+                               MapView, CHUNK_SIZE, load_tilemap};
+    pub use private::particles::{ParticleEmitter, EmitterOptions, ParticleShape};
+    pub use private::camera::Camera2D;
     pub use glium::index::PrimitiveType;
     pub use glium::index::PrimitiveType::*;
 }
@@ -71,13 +85,29 @@ pub mod window {
     //!
     //! The window is created by the Glutin library.
 
-    pub use private::glutin_window::{Window, Frame, WindowOptions};
+    pub use private::glutin_window::{Window, Frame, WindowOptions, VirtualResolution, ScaleMode};
 }
 
 pub mod modifiers {
     //! Many contexts can be modified and chained together in a logical way.
 
     pub use private::raw::Transform;
+    pub use private::affine::Affine2;
+}
+
+pub mod font {
+    //! Loading fonts into the font cache that is shared by a window and
+    //! everything that it draws to.
+
+    pub use private::font::{
+        FontLoader,
+        BakedFont,
+        TextMeasure,
+        TextLayout,
+        LayoutLine,
+        HorizontalAlign,
+        VerticalAlign
+    };
 }
 
 pub mod game {
@@ -95,12 +125,12 @@ pub mod prelude {
     //! recommended for average Lux usage.
 
     pub use color::{Color, rgb, rgba, hsv, hsva};
-    pub use graphics::{Canvas, IntoSprite, Sprite, Texture, Rectangle, Square, Ellipse, Circle, Picture, Line, Pixels};
+    pub use graphics::{Canvas, IntoSprite, Sprite, Texture, Rectangle, Square, Ellipse, Circle, Picture, Line, Pixels, Text, DrawStyle, PathShape, Polygon, Polyline, RoundedRect, Arc, Pie, Ring, NineSlice, TiledPicture, FillRule, LineJoin, LineCap, Dash, Fill};
     pub use interactive::Interactive;
     pub use window::{Window, Frame};
     pub use interactive::EventIterator;
-    pub use modifiers::Transform;
-    //pub use font::{TextDraw};
+    pub use modifiers::{Transform, Affine2};
+    pub use font::{FontLoader, TextMeasure};
     pub use graphics::TextureLoader;
 
     pub use LuxError;
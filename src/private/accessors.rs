@@ -8,10 +8,12 @@ use std::cell::RefCell;
 use super::primitive_canvas::{
     CachedColorDraw,
     CachedTexDraw,
-    StencilState
+    StencilState,
+    SavedState
 };
 
 use super::sprite::Sprite;
+use super::font::BakedFonts;
 use glium;
 use poison_pool;
 use font_atlas::cache::FontCache;
@@ -29,6 +31,7 @@ pub trait DrawLike: StateLike {
 pub struct StateFields<'a> {
     pub display: &'a glium::Display,
     pub font_cache: &'a Rc<RefCell<FontCache<Sprite>>>,
+    pub baked_fonts: &'a Rc<RefCell<BakedFonts>>,
     pub texture_shader: &'a Rc<glium::Program>,
     pub color_shader: &'a Rc<glium::Program>,
 }
@@ -37,7 +40,9 @@ pub struct DrawFields<'a, S: glium::Surface + 'a> {
     pub display: &'a glium::Display,
     pub scissor: &'a mut Option<(u32, u32, u32, u32)>,
     pub stencil_state: &'a mut StencilState,
+    pub state_stack: &'a mut Vec<SavedState>,
     pub font_cache: &'a Rc<RefCell<FontCache<Sprite>>>,
+    pub baked_fonts: &'a Rc<RefCell<BakedFonts>>,
     pub texture_shader: &'a Rc<glium::Program>,
     pub color_shader: &'a Rc<glium::Program>,
     pub color_draw_cache: &'a mut Option<CachedColorDraw>,
@@ -50,7 +55,9 @@ pub struct DrawFieldsRef<'a, S: glium::Surface + 'a> {
     pub display: &'a glium::Display,
     pub scissor: &'a Option<(u32, u32, u32, u32)>,
     pub stencil_state: &'a StencilState,
+    pub state_stack: &'a Vec<SavedState>,
     pub font_cache: &'a Rc<RefCell<FontCache<Sprite>>>,
+    pub baked_fonts: &'a Rc<RefCell<BakedFonts>>,
     pub texture_shader: &'a Rc<glium::Program>,
     pub color_shader: &'a Rc<glium::Program>,
     pub color_draw_cache: &'a Option<CachedColorDraw>,
@@ -0,0 +1,346 @@
+use std::ops::Mul;
+
+use vecmath;
+
+use super::types::Float;
+
+/// A 2d affine transformation stored as a 2x3 matrix.
+///
+/// This holds the same translations, rotations, scales and shears as the
+/// 4x4 matrices used by `Transform`, with less work per point.  The
+/// `transform` of a drawable is a 4x4 matrix, and an `Affine2` can be
+/// given with `Some(affine.into())`.  `Transform::apply_matrix` takes
+/// either form.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::modifiers::Affine2;
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+///# let mut frame = window.cleared_frame(rgb(1.0, 1.0, 1.0));
+/// let spin = Affine2::identity().rotate_around((25.0, 25.0), 0.5);
+/// frame.draw(Square {
+///     size: 50.0,
+///     transform: Some(spin.into()),
+///     .. Default::default()
+/// }).unwrap();
+///# }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Affine2 {
+    /// The rows of the matrix.  A point is transformed by
+    ///
+    /// * `x' = rows[0][0] * x + rows[0][1] * y + rows[0][2]`
+    /// * `y' = rows[1][0] * x + rows[1][1] * y + rows[1][2]`
+    pub rows: [[Float; 3]; 2],
+}
+
+impl Affine2 {
+    /// The transformation that leaves every point where it is.
+    pub fn identity() -> Affine2 {
+        Affine2 { rows: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] }
+    }
+
+    /// A translation by (dx, dy).
+    pub fn translation(dx: Float, dy: Float) -> Affine2 {
+        Affine2 { rows: [[1.0, 0.0, dx], [0.0, 1.0, dy]] }
+    }
+
+    /// A rotation by `theta` radians around the origin.
+    pub fn rotation(theta: Float) -> Affine2 {
+        let (s, c) = theta.sin_cos();
+        Affine2 { rows: [[c, -s, 0.0], [s, c, 0.0]] }
+    }
+
+    /// A scale around the origin.
+    pub fn scaling(sx: Float, sy: Float) -> Affine2 {
+        Affine2 { rows: [[sx, 0.0, 0.0], [0.0, sy, 0.0]] }
+    }
+
+    /// A shear, with the same meaning as `Transform::shear`.
+    pub fn shearing(sx: Float, sy: Float) -> Affine2 {
+        Affine2 { rows: [[1.0, sx, 0.0], [sy, 1.0, 0.0]] }
+    }
+
+    /// Builds a transformation that scales, then rotates, then translates.
+    ///
+    /// This is the opposite of `decompose`.
+    pub fn from_parts(translation: (Float, Float), rotation: Float, scale: (Float, Float))
+    -> Affine2 {
+        Affine2::translation(translation.0, translation.1)
+            * Affine2::rotation(rotation)
+            * Affine2::scaling(scale.0, scale.1)
+    }
+
+    /// Applies a translation before this transformation, like
+    /// `Transform::translate`.
+    pub fn translate(self, dx: Float, dy: Float) -> Affine2 {
+        self * Affine2::translation(dx, dy)
+    }
+
+    /// Applies a rotation before this transformation, like
+    /// `Transform::rotate`.
+    pub fn rotate(self, theta: Float) -> Affine2 {
+        self * Affine2::rotation(theta)
+    }
+
+    /// Applies a scale before this transformation, like
+    /// `Transform::scale`.
+    pub fn scale(self, sx: Float, sy: Float) -> Affine2 {
+        self * Affine2::scaling(sx, sy)
+    }
+
+    /// Applies a shear before this transformation, like
+    /// `Transform::shear`.
+    pub fn shear(self, sx: Float, sy: Float) -> Affine2 {
+        self * Affine2::shearing(sx, sy)
+    }
+
+    /// Applies a rotation around a point before this transformation, like
+    /// `Transform::rotate_around`.
+    pub fn rotate_around(self, point: (Float, Float), theta: Float) -> Affine2 {
+        self.translate(point.0, point.1)
+            .rotate(theta)
+            .translate(-point.0, -point.1)
+    }
+
+    /// Returns the determinant of the 2x2 part of the matrix.  The area of
+    /// a shape is multiplied by this when it is transformed.
+    pub fn determinant(&self) -> Float {
+        let r = &self.rows;
+        r[0][0] * r[1][1] - r[0][1] * r[1][0]
+    }
+
+    /// Returns the transformation that undoes this one, or `None` if it
+    /// squashes everything onto a line or a point.
+    pub fn inverse(&self) -> Option<Affine2> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let r = &self.rows;
+        let (a, b, c, d) = (r[1][1] / det, -r[0][1] / det, -r[1][0] / det, r[0][0] / det);
+        Some(Affine2 {
+            rows: [[a, b, -(a * r[0][2] + b * r[1][2])],
+                   [c, d, -(c * r[0][2] + d * r[1][2])]],
+        })
+    }
+
+    /// Splits the transformation into a translation, a rotation in radians
+    /// and a scale, which `from_parts` puts back together.
+    ///
+    /// Mirroring shows up as a negative y scale.  Shears can't be
+    /// represented, so a sheared transformation won't survive the round
+    /// trip.
+    pub fn decompose(&self) -> ((Float, Float), Float, (Float, Float)) {
+        let r = &self.rows;
+        let sx = (r[0][0] * r[0][0] + r[1][0] * r[1][0]).sqrt();
+        let rotation = r[1][0].atan2(r[0][0]);
+        let sy = if sx == 0.0 {
+            (r[0][1] * r[0][1] + r[1][1] * r[1][1]).sqrt()
+        } else {
+            self.determinant() / sx
+        };
+        ((r[0][2], r[1][2]), rotation, (sx, sy))
+    }
+
+    /// Transforms a point.
+    pub fn transform_point(&self, (x, y): (Float, Float)) -> (Float, Float) {
+        let r = &self.rows;
+        (r[0][0] * x + r[0][1] * y + r[0][2],
+         r[1][0] * x + r[1][1] * y + r[1][2])
+    }
+
+    /// Transforms a direction, leaving out the translation.
+    pub fn transform_vector(&self, (x, y): (Float, Float)) -> (Float, Float) {
+        let r = &self.rows;
+        (r[0][0] * x + r[0][1] * y,
+         r[1][0] * x + r[1][1] * y)
+    }
+
+    /// Transforms the corners of a rectangle given as (x, y, w, h) and
+    /// returns the smallest axis-aligned rectangle that contains them.
+    pub fn transform_rect(&self, rect: (Float, Float, Float, Float))
+    -> (Float, Float, Float, Float) {
+        corner_bounds(rect, |p| self.transform_point(p))
+    }
+}
+
+/// Moves the corners of a rectangle given as (x, y, w, h) with `f` and
+/// returns the smallest axis-aligned rectangle that contains them.
+pub fn corner_bounds<F>(rect: (Float, Float, Float, Float), f: F) -> (Float, Float, Float, Float)
+where F: Fn((Float, Float)) -> (Float, Float) {
+    let (x, y, w, h) = rect;
+    let corners = [f((x, y)), f((x + w, y)), f((x, y + h)), f((x + w, y + h))];
+    let (mut min_x, mut min_y) = corners[0];
+    let (mut max_x, mut max_y) = corners[0];
+    for &(px, py) in &corners[1..] {
+        min_x = min_x.min(px);
+        min_y = min_y.min(py);
+        max_x = max_x.max(px);
+        max_y = max_y.max(py);
+    }
+    (min_x, min_y, max_x - min_x, max_y - min_y)
+}
+
+/// Fails the test unless two points are within `1e-3` of each other.
+#[cfg(test)]
+pub fn assert_point_near(a: (Float, Float), b: (Float, Float)) {
+    assert!((a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3, "{:?} != {:?}", a, b);
+}
+
+impl Default for Affine2 {
+    fn default() -> Affine2 {
+        Affine2::identity()
+    }
+}
+
+/// `a * b` applies `b` first and then `a`, the same order that `Transform`
+/// uses.
+impl Mul for Affine2 {
+    type Output = Affine2;
+    fn mul(self, other: Affine2) -> Affine2 {
+        let (a, b) = (&self.rows, &other.rows);
+        let mut rows = [[0.0; 3]; 2];
+        for i in 0 .. 2 {
+            rows[i][0] = a[i][0] * b[0][0] + a[i][1] * b[1][0];
+            rows[i][1] = a[i][0] * b[0][1] + a[i][1] * b[1][1];
+            rows[i][2] = a[i][0] * b[0][2] + a[i][1] * b[1][2] + a[i][2];
+        }
+        Affine2 { rows: rows }
+    }
+}
+
+/// Keeps the parts of a 4x4 matrix that move x and y.
+///
+/// Points are drawn at a depth of zero and are never divided by w, so the
+/// z row, the z column and the perspective row are dropped.  A matrix that
+/// uses them will not draw the same way after the conversion.
+impl From<[[Float; 4]; 4]> for Affine2 {
+    fn from(m: [[Float; 4]; 4]) -> Affine2 {
+        Affine2 {
+            rows: [[m[0][0], m[1][0], m[3][0]],
+                   [m[0][1], m[1][1], m[3][1]]],
+        }
+    }
+}
+
+impl From<Affine2> for [[Float; 4]; 4] {
+    fn from(a: Affine2) -> [[Float; 4]; 4] {
+        let r = &a.rows;
+        let mut m = vecmath::mat4_id();
+        m[0][0] = r[0][0];
+        m[0][1] = r[1][0];
+        m[1][0] = r[0][1];
+        m[1][1] = r[1][1];
+        m[3][0] = r[0][2];
+        m[3][1] = r[1][2];
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use vecmath;
+
+    use super::{assert_point_near, Affine2};
+    use super::super::types::Float;
+
+    fn assert_near(a: Affine2, b: Affine2) {
+        for i in 0 .. 2 {
+            for j in 0 .. 3 {
+                assert!((a.rows[i][j] - b.rows[i][j]).abs() < 1e-4, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn samples() -> Vec<Affine2> {
+        vec![Affine2::identity(),
+             Affine2::translation(10.0, -4.0),
+             Affine2::from_parts((3.0, 7.0), 0.8, (2.0, 0.5)),
+             Affine2::identity().rotate_around((25.0, 25.0), -2.5).scale(1.5, 1.5),
+             Affine2::scaling(1.0, -3.0).shear(0.3, 0.1).translate(-6.0, 2.0)]
+    }
+
+    const POINTS: [(Float, Float); 3] = [(0.0, 0.0), (1.0, 2.0), (-35.5, 80.0)];
+
+    #[test]
+    fn composing_applies_the_right_side_first() {
+        let a = Affine2::translation(5.0, 0.0);
+        let b = Affine2::scaling(2.0, 2.0);
+        assert_point_near((a * b).transform_point((1.0, 1.0)), (7.0, 2.0));
+        assert_point_near((b * a).transform_point((1.0, 1.0)), (12.0, 2.0));
+        for &x in &samples() {
+            for &y in &samples() {
+                for &p in &POINTS {
+                    assert_point_near((x * y).transform_point(p),
+                                      x.transform_point(y.transform_point(p)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composing_matches_4x4_matrices() {
+        for &x in &samples() {
+            for &y in &samples() {
+                let matrix = vecmath::col_mat4_mul(x.into(), y.into());
+                assert_near(Affine2::from(matrix), x * y);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_the_transformation() {
+        for &t in &samples() {
+            let inverse = t.inverse().unwrap();
+            assert_near(t * inverse, Affine2::identity());
+            assert_near(inverse * t, Affine2::identity());
+            for &p in &POINTS {
+                assert_point_near(inverse.transform_point(t.transform_point(p)), p);
+            }
+        }
+        assert!(Affine2::scaling(0.0, 1.0).inverse().is_none());
+        assert!(Affine2::scaling(Float::NAN, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn decompose_round_trips_through_from_parts() {
+        let parts = [((0.0, 0.0), 0.0, (1.0, 1.0)),
+                     ((3.0, 7.0), 0.8, (2.0, 0.5)),
+                     ((-12.0, 4.5), -2.9, (0.25, 4.0)),
+                     ((1.0, 1.0), 1.2, (3.0, -2.0))];
+        for &(translation, rotation, scale) in &parts {
+            let t = Affine2::from_parts(translation, rotation, scale);
+            let (t2, r2, s2) = t.decompose();
+            assert_point_near(t2, translation);
+            assert!((r2 - rotation).abs() < 1e-4, "{} != {}", r2, rotation);
+            assert_point_near(s2, scale);
+            assert_near(Affine2::from_parts(t2, r2, s2), t);
+        }
+    }
+
+    #[test]
+    fn rects_are_bounded_by_their_corners() {
+        let t = Affine2::identity().rotate_around((1.0, 1.0), PI / 2.0);
+        let (x, y, w, h) = t.transform_rect((0.0, 0.0, 4.0, 2.0));
+        assert_point_near((x, y), (0.0, 0.0));
+        assert_point_near((w, h), (2.0, 4.0));
+    }
+
+    #[test]
+    fn matrices_convert_both_ways() {
+        for &t in &samples() {
+            let matrix: [[Float; 4]; 4] = t.into();
+            assert_near(Affine2::from(matrix), t);
+            for &(x, y) in &POINTS {
+                let by_matrix = (matrix[0][0] * x + matrix[1][0] * y + matrix[3][0],
+                                 matrix[0][1] * x + matrix[1][1] * y + matrix[3][1]);
+                assert_point_near(t.transform_point((x, y)), by_matrix);
+            }
+        }
+    }
+}
@@ -0,0 +1,562 @@
+use std::collections::HashMap;
+use std::vec::Drain;
+
+use super::canvas::Picture;
+use super::sheet_json::{SheetData, TagDirection};
+use super::sprite::{Sprite, UniformSpriteSheet, NonUniformSpriteSheet};
+use super::types::Float;
+
+/// The duration given to frames that don't have one of their own.
+const DEFAULT_FRAME_DURATION: Float = 0.1;
+
+/// How an animation behaves once it reaches its last frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Starts over from the first frame.
+    Loop,
+    /// Plays backwards to the first frame, then forwards again.
+    PingPong,
+    /// Stops on the last frame.
+    Once,
+}
+
+/// An ordered list of frames, each shown for its own duration.
+///
+/// An animation only describes the frames.  Play it with an
+/// `AnimatedSprite`.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    frames: Vec<Sprite>,
+    durations: Vec<Float>,
+    /// What happens after the last frame.
+    pub mode: PlayMode,
+}
+
+/// Something that happened while an `AnimatedSprite` was updated.
+///
+/// Both events carry the name of the clip.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnimationEvent {
+    /// A `Loop` or `PingPong` clip went back to its first frame.
+    Looped(String),
+    /// A `Once` clip reached the end of its last frame.
+    Finished(String),
+}
+
+/// Plays named animation clips.
+///
+/// Call `update` with the `dt` from `Game::update` and draw the sprite that
+/// `sprite` or `picture` returns.  Clips that loop or finish push
+/// `AnimationEvent`s that can be read with `events`.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::{Animation, AnimatedSprite, PlayMode};
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let sheet = window.load_texture_file("./test/minecraft_fixedwidth_font.png")
+///                   .unwrap()
+///                   .into_sprite()
+///                   .as_uniform_sprite_sheet(16, 16);
+/// let mut player = AnimatedSprite::new();
+/// player.add_clip("count", Animation::from_uniform_sheet(
+///     &sheet, &[(0, 3), (1, 3), (2, 3)], 0.2, PlayMode::Loop));
+/// player.play("count");
+///
+/// while window.is_open() {
+///     let mut frame = window.cleared_frame(rgb(1.0, 1.0, 1.0));
+///     player.update(1.0 / 60.0);
+///     frame.draw(player.picture(10.0, 10.0)).unwrap();
+/// }
+///# }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnimatedSprite {
+    clips: HashMap<String, Animation>,
+    current: Option<String>,
+    playhead: Playhead,
+    events: Vec<AnimationEvent>,
+    /// How fast time passes for the animation.  Defaults to `1.0`.
+    pub speed: Float,
+}
+
+/// Where an `AnimatedSprite` is in its current clip.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Playhead {
+    index: usize,
+    time: Float,
+    forward: bool,
+    finished: bool,
+}
+
+/// Panics if a frame duration can't be played.
+fn check_duration(duration: Float) {
+    assert!(duration >= 0.0 && duration.is_finite(),
+            "frame durations must be finite and not negative, got {}", duration);
+}
+
+impl Animation {
+    /// Creates an animation with no frames.
+    pub fn new(mode: PlayMode) -> Animation {
+        Animation {
+            frames: vec![],
+            durations: vec![],
+            mode: mode,
+        }
+    }
+
+    /// Creates an animation where every frame is shown for `duration`
+    /// seconds.
+    ///
+    /// ## Panics
+    /// Panics if `duration` is negative, infinite or NaN.
+    pub fn from_frames(frames: Vec<Sprite>, duration: Float, mode: PlayMode) -> Animation {
+        check_duration(duration);
+        Animation {
+            durations: vec![duration; frames.len()],
+            frames: frames,
+            mode: mode,
+        }
+    }
+
+    /// Creates an animation out of the cells of a uniform sprite sheet.
+    ///
+    /// ## Panics
+    /// Panics if a cell is out of bounds, or if `duration` is negative,
+    /// infinite or NaN.
+    pub fn from_uniform_sheet(sheet: &UniformSpriteSheet, cells: &[(u32, u32)],
+                              duration: Float, mode: PlayMode) -> Animation {
+        let frames = cells.iter().map(|&(x, y)| sheet.get(x, y)).collect();
+        Animation::from_frames(frames, duration, mode)
+    }
+
+    /// Creates an animation out of a tag that was loaded from sprite sheet
+    /// JSON.
+    ///
+    /// Frame durations come from the JSON.  Ping-pong tags become `PingPong`
+    /// animations and every other tag becomes a `Loop` animation.  Returns
+    /// `None` if there is no tag with that name.
+    pub fn from_tag(sheet: &NonUniformSpriteSheet<String>, data: &SheetData, tag: &str)
+    -> Option<Animation> {
+        let tag = match data.tag(tag) {
+            Some(tag) => tag,
+            None => return None,
+        };
+
+        let mode = match tag.direction {
+            TagDirection::PingPong => PlayMode::PingPong,
+            TagDirection::Forward | TagDirection::Reverse => PlayMode::Loop,
+        };
+        let mut animation = Animation::new(mode);
+        for frame in data.tag_frames(tag) {
+            let sprite = match sheet.get_opt(&frame.name[..]) {
+                Some(sprite) => sprite,
+                None => return None,
+            };
+            let duration = frame.duration.map(|ms| ms as Float / 1000.0)
+                                         .unwrap_or(DEFAULT_FRAME_DURATION);
+            animation.push_frame(sprite, duration);
+        }
+        if tag.direction == TagDirection::Reverse {
+            animation.frames.reverse();
+            animation.durations.reverse();
+        }
+        Some(animation)
+    }
+
+    /// Adds a frame to the end of the animation.
+    ///
+    /// ## Panics
+    /// Panics if `duration` is negative, infinite or NaN.
+    pub fn push_frame(&mut self, sprite: Sprite, duration: Float) {
+        check_duration(duration);
+        self.frames.push(sprite);
+        self.durations.push(duration);
+    }
+
+    /// Same as `push_frame`, but can be chained.
+    pub fn with_frame(mut self, sprite: Sprite, duration: Float) -> Animation {
+        self.push_frame(sprite, duration);
+        self
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if the animation has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the sprite of a frame.
+    pub fn frame(&self, index: usize) -> Option<&Sprite> {
+        self.frames.get(index)
+    }
+
+    /// Returns how long a frame is shown for in seconds.
+    pub fn duration(&self, index: usize) -> Option<Float> {
+        self.durations.get(index).cloned()
+    }
+
+    /// Returns how long it takes to play every frame once.
+    pub fn total_duration(&self) -> Float {
+        self.durations.iter().fold(0.0, |total, &d| total + d)
+    }
+}
+
+impl AnimatedSprite {
+    /// Creates an animated sprite without any clips.
+    pub fn new() -> AnimatedSprite {
+        AnimatedSprite {
+            clips: HashMap::new(),
+            current: None,
+            playhead: Playhead::start(),
+            events: vec![],
+            speed: 1.0,
+        }
+    }
+
+    /// Creates an animated sprite that is playing a single animation under
+    /// the name `"default"`.
+    pub fn from_animation(animation: Animation) -> AnimatedSprite {
+        let mut sprite = AnimatedSprite::new();
+        sprite.add_clip("default", animation);
+        sprite.play("default");
+        sprite
+    }
+
+    /// Adds a named clip, replacing any clip that already has that name.
+    pub fn add_clip<S: Into<String>>(&mut self, name: S, animation: Animation) {
+        self.clips.insert(name.into(), animation);
+    }
+
+    /// Returns a clip by name.
+    pub fn clip(&self, name: &str) -> Option<&Animation> {
+        self.clips.get(name)
+    }
+
+    /// Switches to a clip and plays it from the start.
+    ///
+    /// Nothing happens if the clip is already playing, so this can be
+    /// called every update.  Returns false if there is no clip with that
+    /// name.
+    pub fn play(&mut self, name: &str) -> bool {
+        if !self.clips.contains_key(name) {
+            return false;
+        }
+        if self.current.as_ref().map(|c| &c[..] != name).unwrap_or(true) {
+            self.current = Some(name.to_string());
+            self.restart();
+        }
+        true
+    }
+
+    /// Plays the current clip again from its first frame.
+    pub fn restart(&mut self) {
+        self.playhead = Playhead::start();
+    }
+
+    /// Returns the name of the clip that is playing.
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current.as_ref().map(|c| &c[..])
+    }
+
+    /// Returns the index of the frame that is being shown.
+    pub fn frame_index(&self) -> usize {
+        self.playhead.index
+    }
+
+    /// Returns true if a `Once` clip has reached its end.
+    pub fn is_finished(&self) -> bool {
+        self.playhead.finished
+    }
+
+    /// Moves the animation forward by `dt` seconds.
+    ///
+    /// A `Loop` or `PingPong` clip pushes at most one `Looped` event per
+    /// update, no matter how many times it went around.
+    pub fn update(&mut self, dt: Float) {
+        let (name, animation) = match self.current {
+            Some(ref name) => match self.clips.get(name) {
+                Some(animation) => (name, animation),
+                None => return,
+            },
+            None => return,
+        };
+
+        let (looped, finished) = self.playhead.advance(&animation.durations, animation.mode,
+                                                       dt * self.speed);
+        if looped {
+            self.events.push(AnimationEvent::Looped(name.clone()));
+        }
+        if finished {
+            self.events.push(AnimationEvent::Finished(name.clone()));
+        }
+    }
+
+    /// Returns the sprite for the frame that is being shown.
+    pub fn sprite(&self) -> Option<&Sprite> {
+        self.current.as_ref()
+                    .and_then(|name| self.clips.get(name))
+                    .and_then(|animation| animation.frame(self.playhead.index))
+    }
+
+    /// Returns a picture of the frame that is being shown.
+    pub fn picture(&self, x: Float, y: Float) -> Picture {
+        Picture {
+            sprite: self.sprite(),
+            x: x,
+            y: y,
+            .. Default::default()
+        }
+    }
+
+    /// Takes the events that happened since the last time that this was
+    /// called.
+    pub fn events(&mut self) -> Drain<AnimationEvent> {
+        self.events.drain(..)
+    }
+}
+
+impl Playhead {
+    fn start() -> Playhead {
+        Playhead {
+            index: 0,
+            time: 0.0,
+            forward: true,
+            finished: false,
+        }
+    }
+
+    /// Moves forward by `dt` seconds through frames that last `durations`.
+    ///
+    /// Returns whether the clip went back to its first frame and whether it
+    /// finished.
+    fn advance(&mut self, durations: &[Float], mode: PlayMode, dt: Float) -> (bool, bool) {
+        let total = durations.iter().fold(0.0, |total, &d| total + d);
+        // Without this, frames with no duration would never stop advancing.
+        if self.finished || total <= 0.0 || !(dt > 0.0) || !dt.is_finite() {
+            return (false, false);
+        }
+
+        let len = durations.len();
+        let mut looped = false;
+        self.time += dt;
+
+        // Whole cycles end where they started, so skip them in one go
+        // instead of stepping through every frame of every cycle.
+        let cycle = match mode {
+            PlayMode::Loop => total,
+            PlayMode::PingPong if len > 1 => total * 2.0 - durations[0] - durations[len - 1],
+            PlayMode::PingPong => total,
+            PlayMode::Once => 0.0,
+        };
+        if cycle > 0.0 && self.time >= cycle {
+            self.time %= cycle;
+            looped = true;
+        }
+
+        while self.time >= durations[self.index] {
+            self.time -= durations[self.index];
+
+            match mode {
+                PlayMode::Once if self.index + 1 == len => {
+                    self.time = 0.0;
+                    self.finished = true;
+                    return (looped, true);
+                }
+                PlayMode::PingPong if len > 1 => {
+                    if self.forward && self.index + 1 == len {
+                        self.forward = false;
+                    } else if !self.forward && self.index == 0 {
+                        self.forward = true;
+                        looped = true;
+                    }
+                    if self.forward {
+                        self.index += 1;
+                    } else {
+                        self.index -= 1;
+                    }
+                }
+                _ => {
+                    self.index += 1;
+                    if self.index == len {
+                        self.index = 0;
+                        looped = true;
+                    }
+                }
+            }
+        }
+        (looped, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animation, AnimatedSprite, AnimationEvent, PlayMode, Playhead};
+    use super::super::types::Float;
+
+    // Sprites need a window, so these clips only have durations.
+    fn clip(durations: &[Float], mode: PlayMode) -> Animation {
+        Animation {
+            frames: vec![],
+            durations: durations.to_vec(),
+            mode: mode,
+        }
+    }
+
+    fn playing(durations: &[Float], mode: PlayMode) -> AnimatedSprite {
+        AnimatedSprite::from_animation(clip(durations, mode))
+    }
+
+    fn events(player: &mut AnimatedSprite) -> Vec<AnimationEvent> {
+        player.events().collect()
+    }
+
+    fn looped() -> AnimationEvent {
+        AnimationEvent::Looped("default".to_string())
+    }
+
+    #[test]
+    fn loop_steps_through_frames_and_wraps() {
+        let mut player = playing(&[1.0, 1.0, 1.0], PlayMode::Loop);
+        let mut seen = vec![];
+        for _ in 0..7 {
+            player.update(1.0);
+            seen.push(player.frame_index());
+        }
+        assert_eq!(seen, vec![1, 2, 0, 1, 2, 0, 1]);
+        assert_eq!(events(&mut player), vec![looped(), looped()]);
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_bounces_between_the_ends() {
+        let mut player = playing(&[1.0, 1.0, 1.0], PlayMode::PingPong);
+        let mut seen = vec![];
+        for _ in 0..6 {
+            player.update(1.0);
+            seen.push(player.frame_index());
+        }
+        assert_eq!(seen, vec![1, 2, 1, 0, 1, 2]);
+        assert_eq!(events(&mut player), vec![looped()]);
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let mut player = playing(&[1.0, 1.0], PlayMode::Once);
+        player.update(1.0);
+        assert_eq!(player.frame_index(), 1);
+        assert!(events(&mut player).is_empty());
+
+        player.update(1.0);
+        assert_eq!(player.frame_index(), 1);
+        assert!(player.is_finished());
+        assert_eq!(events(&mut player), vec![AnimationEvent::Finished("default".to_string())]);
+
+        player.update(10.0);
+        assert_eq!(player.frame_index(), 1);
+        assert!(events(&mut player).is_empty());
+    }
+
+    #[test]
+    fn large_steps_skip_whole_cycles() {
+        let mut player = playing(&[1.0, 2.0], PlayMode::Loop);
+        player.update(3.0 * 1000.0 + 1.5);
+        assert_eq!(player.frame_index(), 1);
+        assert_eq!(events(&mut player), vec![looped()]);
+
+        // A ping-pong cycle over [1, 2, 3] is 1 + 2 + 3 + 2 = 8 seconds.
+        let mut player = playing(&[1.0, 2.0, 3.0], PlayMode::PingPong);
+        player.update(8.0 * 1000.0 + 6.5);
+        assert_eq!(player.frame_index(), 1);
+        assert_eq!(events(&mut player), vec![looped()]);
+    }
+
+    #[test]
+    fn steps_that_cannot_be_played_are_ignored() {
+        use std::f32;
+
+        let mut player = playing(&[1.0, 1.0], PlayMode::Loop);
+        for &dt in &[f32::INFINITY, f32::NEG_INFINITY, f32::NAN, -1.0, 0.0] {
+            player.update(dt);
+        }
+        player.speed = f32::INFINITY;
+        player.update(1.0);
+        assert_eq!(player.playhead, Playhead::start());
+        assert!(events(&mut player).is_empty());
+    }
+
+    #[test]
+    fn speed_scales_time() {
+        let mut player = playing(&[1.0, 1.0], PlayMode::Loop);
+        player.speed = 2.0;
+        player.update(0.5);
+        assert_eq!(player.frame_index(), 1);
+    }
+
+    #[test]
+    fn zero_duration_clips_do_not_advance() {
+        let mut player = playing(&[0.0, 0.0], PlayMode::Loop);
+        player.update(1.0);
+        assert_eq!(player.frame_index(), 0);
+        assert!(events(&mut player).is_empty());
+
+        let mut player = playing(&[], PlayMode::Loop);
+        player.update(1.0);
+        assert_eq!(player.frame_index(), 0);
+    }
+
+    #[test]
+    fn zero_duration_frames_are_skipped() {
+        let mut player = playing(&[1.0, 0.0, 1.0], PlayMode::Loop);
+        player.update(1.0);
+        assert_eq!(player.frame_index(), 2);
+    }
+
+    #[test]
+    fn play_only_restarts_other_clips() {
+        let mut player = AnimatedSprite::new();
+        player.add_clip("walk", clip(&[1.0, 1.0, 1.0], PlayMode::Loop));
+        player.add_clip("jump", clip(&[1.0, 1.0], PlayMode::Once));
+        assert!(!player.play("swim"));
+        assert_eq!(player.current_clip(), None);
+
+        assert!(player.play("walk"));
+        player.update(1.0);
+        assert!(player.play("walk"));
+        assert_eq!(player.frame_index(), 1);
+
+        assert!(player.play("jump"));
+        assert_eq!(player.current_clip(), Some("jump"));
+        assert_eq!(player.frame_index(), 0);
+    }
+
+    #[test]
+    fn restart_clears_finished() {
+        let mut player = playing(&[1.0], PlayMode::Once);
+        player.update(1.0);
+        assert!(player.is_finished());
+
+        player.restart();
+        assert!(!player.is_finished());
+        assert_eq!(player.playhead, Playhead::start());
+        player.update(1.0);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_durations_are_rejected() {
+        Animation::from_frames(vec![], -1.0, PlayMode::Loop);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_durations_are_rejected() {
+        Animation::from_frames(vec![], ::std::f32::NAN, PlayMode::Loop);
+    }
+}
@@ -0,0 +1,282 @@
+use glium;
+use image;
+
+use std::rc::Rc;
+use std::path::Path;
+
+use super::accessors::StateLike;
+use super::error::{LuxError, LuxResult};
+use super::sprite::{self, Sprite};
+
+/// Options used to create a `TextureAtlas`.
+#[derive(Copy, Clone, Debug)]
+pub struct AtlasOptions {
+    /// The size of each page in pixels.
+    ///
+    /// Depending on the graphics card, the width and height might need
+    /// to be powers of two.
+    pub page_size: (u32, u32),
+    /// The number of empty pixels kept between images and around the edges
+    /// of a page so that filtering doesn't bleed neighbouring images into
+    /// each other.
+    pub padding: u32,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> AtlasOptions {
+        AtlasOptions {
+            page_size: (1024, 1024),
+            padding: 2,
+        }
+    }
+}
+
+/// Packs many small images into a few large textures.
+///
+/// Every image that is added to the atlas is returned as a `Sprite` that
+/// is a `sub_sprite` of one of the atlas pages.  Sprites that share a page
+/// share a texture, so drawing them one after another gets batched into a
+/// single draw call.
+///
+/// Images can be added at any time.  When an image doesn't fit on any of
+/// the existing pages a new page is created.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::TextureAtlas;
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let mut atlas = TextureAtlas::new_with_defaults();
+/// let player = atlas.load_file(&window, "./test/test.png").unwrap();
+/// let enemy = atlas.load_file(&window, "./test/minecraft_fixedwidth_font.png").unwrap();
+///# }
+/// ```
+pub struct TextureAtlas {
+    options: AtlasOptions,
+    pages: Vec<Page>,
+}
+
+struct Page {
+    sprite: Sprite,
+    packer: ShelfPacker,
+}
+
+/// Finds spots for images on a page by stacking rows of images on top of
+/// each other.
+struct ShelfPacker {
+    shelves: Vec<Shelf>,
+}
+
+/// A row of images on a page that are all at most `height` tall.
+struct Shelf {
+    y: u32,
+    height: u32,
+    next_x: u32,
+}
+
+impl Page {
+    fn new<D: StateLike>(d: &D, (w, h): (u32, u32)) -> LuxResult<Page> {
+        use glium::texture::{RawImage2d, MipmapsOption, Texture2d};
+
+        // Start out fully transparent so that the padding stays invisible.
+        let blank = RawImage2d::from_raw_rgba(vec![0u8; (w * h * 4) as usize], (w, h));
+        // Pages are written to after they are created, which would leave any
+        // mipmaps out of date.
+        let texture = try!(Texture2d::with_mipmaps(d.state_fields().display, blank,
+                                                   MipmapsOption::NoMipmap));
+        Ok(Page {
+            sprite: sprite::sprite_from_texture(Rc::new(texture)),
+            packer: ShelfPacker { shelves: vec![] },
+        })
+    }
+}
+
+impl ShelfPacker {
+    /// Finds a spot for a `w` by `h` image, returning its top left corner.
+    fn allocate(&mut self, w: u32, h: u32, options: &AtlasOptions) -> Option<(u32, u32)> {
+        let (page_w, page_h) = options.page_size;
+        let padding = options.padding;
+
+        // Use the shortest shelf that the image fits on.
+        let mut best: Option<usize> = None;
+        for (i, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height >= h && shelf.next_x + w + padding <= page_w {
+                let better = match best {
+                    Some(b) => shelf.height < self.shelves[b].height,
+                    None => true,
+                };
+                if better {
+                    best = Some(i);
+                }
+            }
+        }
+
+        let best = match best {
+            Some(b) => b,
+            None => {
+                let y = self.shelves.last()
+                                    .map(|s| s.y + s.height + padding)
+                                    .unwrap_or(padding);
+                if padding + w + padding > page_w || y + h + padding > page_h {
+                    return None;
+                }
+                self.shelves.push(Shelf { y: y, height: h, next_x: padding });
+                self.shelves.len() - 1
+            }
+        };
+
+        let shelf = &mut self.shelves[best];
+        let pos = (shelf.next_x, shelf.y);
+        shelf.next_x += w + padding;
+        Some(pos)
+    }
+}
+
+impl TextureAtlas {
+    /// Creates an empty atlas.  Pages are only created once images are
+    /// added.
+    pub fn new(options: AtlasOptions) -> TextureAtlas {
+        TextureAtlas {
+            options: options,
+            pages: vec![],
+        }
+    }
+
+    /// Creates an empty atlas with 1024x1024 pages and 2 pixels of padding.
+    pub fn new_with_defaults() -> TextureAtlas {
+        TextureAtlas::new(AtlasOptions::default())
+    }
+
+    /// Returns the options that this atlas was created with.
+    pub fn options(&self) -> AtlasOptions {
+        self.options
+    }
+
+    /// Returns the number of pages in this atlas.
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns a sprite covering an entire page of the atlas.
+    pub fn page(&self, index: usize) -> Option<Sprite> {
+        self.pages.get(index).map(|p| p.sprite.clone())
+    }
+
+    /// Loads an image from a file and adds it to the atlas.
+    pub fn load_file<D, P: ?Sized>(&mut self, d: &D, path: &P) -> LuxResult<Sprite>
+    where D: StateLike, P: AsRef<Path> {
+        let img = try!(image::open(path));
+        self.add_image(d, img)
+    }
+
+    /// Adds an image to the atlas and returns the sprite that it was packed
+    /// into.
+    pub fn add_image<D: StateLike>(&mut self, d: &D, img: image::DynamicImage)
+    -> LuxResult<Sprite> {
+        let img = img.to_rgba();
+        let (w, h) = img.dimensions();
+
+        let (page_w, page_h) = self.options.page_size;
+        let padding = self.options.padding;
+        if w + padding * 2 > page_w || h + padding * 2 > page_h {
+            return Err(LuxError::AtlasError(
+                format!("A {}x{} image doesn't fit into a {}x{} atlas page with {} pixels of padding",
+                        w, h, page_w, page_h, padding)));
+        }
+
+        let mut spot = None;
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            if let Some(pos) = page.packer.allocate(w, h, &self.options) {
+                spot = Some((i, pos));
+                break;
+            }
+        }
+
+        let (index, (x, y)) = match spot {
+            Some(spot) => spot,
+            None => {
+                let mut page = try!(Page::new(d, self.options.page_size));
+                let pos = page.packer.allocate(w, h, &self.options)
+                              .expect("an image that fits on a page fits on an empty page");
+                self.pages.push(page);
+                (self.pages.len() - 1, pos)
+            }
+        };
+
+        let page = &self.pages[index].sprite;
+
+        // Flipped the same way that `TextureLoader` flips whole images, so
+        // atlased sprites face the same way as sprites loaded directly.
+        let data = glium::texture::RawImage2d::from_raw_rgba_reversed(img.into_raw(), (w, h));
+        page.texture_ref().write(glium::Rect { left: x, bottom: y, width: w, height: h }, data);
+
+        Ok(page.sub_sprite((x, y), (w, h)).expect("allocated spots are inside of the page"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtlasOptions, ShelfPacker};
+
+    fn options(w: u32, h: u32, padding: u32) -> AtlasOptions {
+        AtlasOptions { page_size: (w, h), padding: padding }
+    }
+
+    #[test]
+    fn fills_a_shelf_from_left_to_right() {
+        let options = options(100, 100, 2);
+        let mut packer = ShelfPacker { shelves: vec![] };
+        assert_eq!(packer.allocate(10, 10, &options), Some((2, 2)));
+        assert_eq!(packer.allocate(10, 5, &options), Some((14, 2)));
+        assert_eq!(packer.shelves.len(), 1);
+    }
+
+    #[test]
+    fn starts_a_new_shelf_when_a_row_is_full() {
+        let options = options(30, 100, 2);
+        let mut packer = ShelfPacker { shelves: vec![] };
+        assert_eq!(packer.allocate(10, 10, &options), Some((2, 2)));
+        assert_eq!(packer.allocate(10, 10, &options), Some((14, 2)));
+        assert_eq!(packer.allocate(10, 10, &options), Some((2, 14)));
+    }
+
+    #[test]
+    fn taller_images_get_their_own_shelf() {
+        let options = options(100, 100, 0);
+        let mut packer = ShelfPacker { shelves: vec![] };
+        assert_eq!(packer.allocate(10, 10, &options), Some((0, 0)));
+        assert_eq!(packer.allocate(10, 20, &options), Some((0, 10)));
+        // Short images go on the shortest shelf that they fit on.
+        assert_eq!(packer.allocate(10, 5, &options), Some((10, 0)));
+        assert_eq!(packer.allocate(10, 15, &options), Some((10, 10)));
+    }
+
+    #[test]
+    fn spots_never_overlap_or_leave_the_page() {
+        let options = options(64, 64, 1);
+        let mut packer = ShelfPacker { shelves: vec![] };
+        let mut placed: Vec<(u32, u32, u32, u32)> = vec![];
+        for i in 0 .. 200 {
+            let (w, h) = (3 + i % 7, 2 + (i * 5) % 9);
+            if let Some((x, y)) = packer.allocate(w, h, &options) {
+                assert!(x >= 1 && y >= 1 && x + w + 1 <= 64 && y + h + 1 <= 64);
+                for &(px, py, pw, ph) in &placed {
+                    assert!(x + w + 1 <= px || px + pw + 1 <= x || y + h + 1 <= py || py + ph + 1 <= y);
+                }
+                placed.push((x, y, w, h));
+            }
+        }
+        assert!(!placed.is_empty());
+    }
+
+    #[test]
+    fn returns_none_when_the_page_is_full() {
+        let options = options(20, 20, 0);
+        let mut packer = ShelfPacker { shelves: vec![] };
+        assert_eq!(packer.allocate(20, 15, &options), Some((0, 0)));
+        assert_eq!(packer.allocate(5, 5, &options), Some((0, 15)));
+        assert_eq!(packer.allocate(5, 6, &options), None);
+        assert_eq!(packer.allocate(21, 1, &options), None);
+    }
+}
@@ -0,0 +1,289 @@
+use super::affine::Affine2;
+use super::canvas::Canvas;
+use super::raw::Transform;
+use super::types::Float;
+
+/// A view into a 2d world that can be panned, zoomed and rotated.
+///
+/// `position` is the point in the world that is shown in the center of the
+/// viewport.  Apply the camera to a canvas with `apply` or `with_view` and
+/// everything drawn afterwards is drawn in world coordinates.  Use
+/// `screen_to_world` to find out what is under the mouse.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::Camera2D;
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let mut camera = Camera2D::new(window.width(), window.height());
+/// camera.zoom = 2.0;
+///
+/// while window.is_open() {
+///     let (mx, my) = camera.screen_to_world(window.mouse_pos());
+///     let mut frame = window.cleared_frame(rgb(1.0, 1.0, 1.0));
+///     camera.with_view(&mut frame, |frame| {
+///         frame.draw(Square { x: mx, y: my, size: 5.0, .. Default::default() }).unwrap();
+///     });
+/// }
+///# }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera2D {
+    /// The point in the world at the center of the viewport.
+    pub position: (Float, Float),
+    /// How many pixels a unit of the world covers.  Values bigger than
+    /// `1.0` zoom in.
+    pub zoom: Float,
+    /// The rotation of the camera in radians.  The world appears rotated
+    /// by the opposite amount.
+    pub rotation: Float,
+    /// The part of the canvas that the camera draws into as (x, y, w, h)
+    /// in pixels.
+    pub viewport: (Float, Float, Float, Float),
+}
+
+impl Camera2D {
+    /// Creates a camera that covers a `width` by `height` canvas and shows
+    /// the world from (0, 0) to (width, height), just like a canvas without
+    /// a camera.
+    pub fn new(width: Float, height: Float) -> Camera2D {
+        Camera2D {
+            position: (width / 2.0, height / 2.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: (0.0, 0.0, width, height),
+        }
+    }
+
+    /// Returns the center of the viewport in pixels.
+    fn viewport_center(&self) -> (Float, Float) {
+        let (x, y, w, h) = self.viewport;
+        (x + w / 2.0, y + h / 2.0)
+    }
+
+    /// Returns the transformation that takes points in the world to points
+    /// on the canvas.
+    pub fn transform(&self) -> Affine2 {
+        let (cx, cy) = self.viewport_center();
+        Affine2::translation(cx, cy)
+            .scale(self.zoom, self.zoom)
+            .rotate(-self.rotation)
+            .translate(-self.position.0, -self.position.1)
+    }
+
+    /// Same as `transform`, but as a 4x4 matrix.
+    pub fn matrix(&self) -> [[Float; 4]; 4] {
+        self.transform().into()
+    }
+
+    /// Multiplies the camera matrix into a canvas, so that everything that
+    /// is drawn afterwards is drawn in world coordinates.
+    pub fn apply<T: Transform>(&self, target: &mut T) {
+        target.apply_matrix(self.transform());
+    }
+
+    /// Calls `f` with the camera applied to the canvas and drawing limited
+    /// to the viewport.  The matrix of the canvas is put back afterwards.
+    pub fn with_view<C, F, R>(&self, canvas: &mut C, f: F) -> R
+    where C: Canvas + Transform, F: FnOnce(&mut C) -> R {
+        // The scissor has to stay on the canvas.
+        let (x, y, w, h) = self.viewport;
+        let (cw, ch) = canvas.size();
+        let (x0, y0) = (x.max(0.0).min(cw), y.max(0.0).min(ch));
+        let (x1, y1) = ((x + w).min(cw).max(x0), (y + h).min(ch).max(y0));
+        let (x, y, w, h) = (x0, y0, x1 - x0, y1 - y0);
+        let camera = *self;
+        canvas.with_scissor(x as u32, y as u32, w as u32, h as u32, move |canvas| {
+            canvas.with_matrix(move |canvas| {
+                camera.apply(canvas);
+                f(canvas)
+            })
+        })
+    }
+
+    /// Returns the transformation that takes points on the canvas to points
+    /// in the world.
+    ///
+    /// A zoom of zero squashes the whole world into `position`, so every
+    /// point on the canvas is taken back to `position`.
+    fn inverse(&self) -> Affine2 {
+        match self.transform().inverse() {
+            Some(inverse) => inverse,
+            None => Affine2::translation(self.position.0, self.position.1).scale(0.0, 0.0),
+        }
+    }
+
+    /// Turns a point on the canvas, such as `Interactive::mouse_pos`, into
+    /// a point in the world.
+    pub fn screen_to_world(&self, point: (Float, Float)) -> (Float, Float) {
+        self.inverse().transform_point(point)
+    }
+
+    /// Turns a point in the world into a point on the canvas.
+    pub fn world_to_screen(&self, point: (Float, Float)) -> (Float, Float) {
+        self.transform().transform_point(point)
+    }
+
+    /// Returns the smallest rectangle in the world that contains everything
+    /// that the camera can see, as (x, y, w, h).
+    pub fn visible_rect(&self) -> (Float, Float, Float, Float) {
+        self.inverse().transform_rect(self.viewport)
+    }
+
+    /// Moves the camera just enough to keep `target` inside of a
+    /// `dead_zone` sized box around its position.
+    ///
+    /// The dead zone is given as (width, height) in world units.  A dead
+    /// zone of `(0.0, 0.0)` keeps the target centered.
+    pub fn follow(&mut self, target: (Float, Float), dead_zone: (Float, Float)) {
+        let (half_w, half_h) = (dead_zone.0 / 2.0, dead_zone.1 / 2.0);
+        self.position.0 = follow_axis(self.position.0, target.0, half_w);
+        self.position.1 = follow_axis(self.position.1, target.1, half_h);
+    }
+
+    /// Same as `follow`, but eases the camera toward the target instead of
+    /// snapping to it.
+    ///
+    /// `stiffness` is how quickly the camera catches up; higher values
+    /// catch up faster.  Pass the `dt` from `Game::update`.
+    pub fn follow_smoothly(&mut self, target: (Float, Float), dead_zone: (Float, Float),
+                           stiffness: Float, dt: Float) {
+        let before = self.position;
+        self.follow(target, dead_zone);
+        // Framerate independent easing.
+        let t = 1.0 - (-stiffness * dt).exp();
+        self.position.0 = before.0 + (self.position.0 - before.0) * t;
+        self.position.1 = before.1 + (self.position.1 - before.1) * t;
+    }
+
+    /// Moves the camera so that it doesn't show anything outside of
+    /// `bounds`, given as (x, y, w, h) in world units.
+    ///
+    /// When the bounds are smaller than what the camera can see, the camera
+    /// is centered on them instead.
+    pub fn clamp_to(&mut self, bounds: (Float, Float, Float, Float)) {
+        let (_, _, view_w, view_h) = self.visible_rect();
+        let (bx, by, bw, bh) = bounds;
+        self.position.0 = clamp_axis(self.position.0, bx, bw, view_w / 2.0);
+        self.position.1 = clamp_axis(self.position.1, by, bh, view_h / 2.0);
+    }
+}
+
+fn follow_axis(position: Float, target: Float, half_zone: Float) -> Float {
+    if target < position - half_zone {
+        target + half_zone
+    } else if target > position + half_zone {
+        target - half_zone
+    } else {
+        position
+    }
+}
+
+fn clamp_axis(position: Float, start: Float, len: Float, half_view: Float) -> Float {
+    if len <= half_view * 2.0 {
+        start + len / 2.0
+    } else {
+        position.max(start + half_view).min(start + len - half_view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vecmath;
+
+    use super::Camera2D;
+    use super::super::affine::assert_point_near as assert_near;
+    use super::super::raw::Transform;
+    use super::super::types::Float;
+
+    fn cameras() -> Vec<Camera2D> {
+        let mut moved = Camera2D::new(800.0, 600.0);
+        moved.position = (100.0, 50.0);
+        moved.zoom = 2.5;
+        moved.rotation = 0.7;
+        moved.viewport = (10.0, 20.0, 400.0, 300.0);
+
+        let mut zoomed_out = Camera2D::new(320.0, 240.0);
+        zoomed_out.zoom = 0.25;
+        zoomed_out.rotation = -3.0;
+
+        vec![Camera2D::new(800.0, 600.0), moved, zoomed_out]
+    }
+
+    const POINTS: [(Float, Float); 4] = [(0.0, 0.0), (13.0, -7.0), (300.0, 200.0), (-450.5, 12.25)];
+
+    #[test]
+    fn new_cameras_show_the_canvas_as_it_is() {
+        let camera = Camera2D::new(800.0, 600.0);
+        for &p in &POINTS {
+            assert_near(camera.world_to_screen(p), p);
+            assert_near(camera.screen_to_world(p), p);
+        }
+        assert_eq!(camera.visible_rect(), (0.0, 0.0, 800.0, 600.0));
+    }
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        for camera in cameras() {
+            for &p in &POINTS {
+                assert_near(camera.screen_to_world(camera.world_to_screen(p)), p);
+                assert_near(camera.world_to_screen(camera.screen_to_world(p)), p);
+            }
+        }
+    }
+
+    #[test]
+    fn world_to_screen_matches_the_matrix() {
+        for camera in cameras() {
+            let (cx, cy) = (camera.viewport.0 + camera.viewport.2 / 2.0,
+                            camera.viewport.1 + camera.viewport.3 / 2.0);
+            let mut m = vecmath::mat4_id();
+            m.translate(cx, cy)
+             .scale(camera.zoom, camera.zoom)
+             .rotate(-camera.rotation)
+             .translate(-camera.position.0, -camera.position.1);
+            for &(x, y) in &POINTS {
+                let by_matrix = (m[0][0] * x + m[1][0] * y + m[3][0],
+                                 m[0][1] * x + m[1][1] * y + m[3][1]);
+                assert_near(camera.world_to_screen((x, y)), by_matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_zoom_shows_only_the_position() {
+        let mut camera = Camera2D::new(100.0, 100.0);
+        camera.zoom = 0.0;
+        assert_near(camera.screen_to_world((10.0, 90.0)), camera.position);
+        assert_eq!(camera.visible_rect(), (50.0, 50.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn position_is_shown_at_the_viewport_center() {
+        for camera in cameras() {
+            let (x, y, w, h) = camera.viewport;
+            assert_near(camera.world_to_screen(camera.position), (x + w / 2.0, y + h / 2.0));
+        }
+    }
+
+    #[test]
+    fn follow_keeps_the_target_in_the_dead_zone() {
+        let mut camera = Camera2D::new(100.0, 100.0);
+        camera.follow((55.0, 45.0), (20.0, 20.0));
+        assert_eq!(camera.position, (50.0, 50.0));
+        camera.follow((200.0, 50.0), (20.0, 20.0));
+        assert_eq!(camera.position, (190.0, 50.0));
+        camera.follow((0.0, 0.0), (0.0, 0.0));
+        assert_eq!(camera.position, (0.0, 0.0));
+    }
+
+    #[test]
+    fn clamp_to_keeps_the_view_inside_the_bounds() {
+        let mut camera = Camera2D::new(100.0, 100.0);
+        camera.position = (190.0, 50.0);
+        camera.clamp_to((0.0, 0.0, 150.0, 60.0));
+        // Wide enough to scroll horizontally, too short to scroll vertically.
+        assert_eq!(camera.position, (100.0, 30.0));
+    }
+}
@@ -1,15 +1,20 @@
-use super::primitive_canvas::{PrimitiveCanvas, StencilState, StencilType};
-use super::types::Float;
-use super::gfx_integration::{ColorVertex, TexVertex};
+use super::primitive_canvas::{PrimitiveCanvas, StencilState, StencilType, SavedState};
+use super::types::{Float, Idx};
+use super::gfx_integration::{ColorVertex, TexVertex, Sampler};
 use super::color::{Color, rgb};
-use super::raw::Transform;
 use super::sprite::Sprite;
 use super::accessors::DrawLike;
-use ::LuxResult;
+use super::tessellate::{fill_convex, fill_contours, stroke_polyline, signed_area,
+                        Dash, FillRule, LineCap, LineJoin, StrokeStyle};
+use super::fill::Fill;
+use super::affine::Affine2;
+use super::font::{ensure_face, layout, place_glyphs, HorizontalAlign, VerticalAlign};
+use ::{LuxResult, LuxError};
 
-use ::vecmath;
+use std::f32::consts::PI;
+use std::ops::{Deref, DerefMut};
 
-use glium::index::PrimitiveType::{TriangleFan, TrianglesList, Points};
+use glium::index::PrimitiveType::{TrianglesList, Points};
 
 pub trait Drawable {
     fn draw<C: Canvas>(self, target: &mut C) -> LuxResult<()>;
@@ -76,30 +81,148 @@ pub trait Canvas: DrawLike + Sized {
     /// provided rectangle.
     fn with_scissor<F, R>(&mut self, x: u32, y: u32, w: u32, h: u32, f: F) -> R
     where F: FnOnce(&mut Self) -> R {
-        // Flush things that we don't want scissored.
-        self.flush_draw().unwrap();
-
-        let view_height = self.height() as u32;
-        let old = self.draw_fields().take_scissor();
+        let old = *self.draw_fields_ref().scissor;
         // TODO: merge these rectangles
-        self.draw_fields().set_scissor(Some((x, view_height - h - y, w, h)));
+        self.set_scissor(Some((x, y, w, h)));
         let res = f(self);
         self.flush_draw().unwrap();
         self.draw_fields().set_scissor(old);
         res
     }
 
+    /// Limits drawing to a rectangle given as (x, y, w, h) until the
+    /// scissor is changed again.  `None` lets drawing go anywhere.
+    ///
+    /// This is the same as `with_scissor` without a closure.  Use
+    /// `push_state` and `pop_state` to put the old scissor back.
+    fn set_scissor(&mut self, rect: Option<(u32, u32, u32, u32)>) {
+        // Flush things that we don't want scissored.
+        self.flush_draw().unwrap();
+
+        let view_height = self.height() as u32;
+        let rect = rect.map(|(x, y, w, h)| (x, view_height - h - y, w, h));
+        self.draw_fields().set_scissor(rect);
+    }
+
     /// Executes a drawing function where all drawing is done on the
     /// stencil buffer.
     fn draw_to_stencil<R, S>(&mut self, typ: StencilType, stencil_fn: S) -> R
     where S: FnOnce(&mut Self) -> R {
+        self.begin_stencil(typ);
+        let res1 = stencil_fn(self);
+        self.end_stencil();
+        res1
+    }
+
+    /// Starts drawing into the stencil buffer.  Everything that is drawn
+    /// until `end_stencil` is called goes into the stencil buffer instead
+    /// of onto the canvas.
+    ///
+    /// This is the same as `draw_to_stencil` without a closure.
+    fn begin_stencil(&mut self, typ: StencilType) {
         self.flush_draw().unwrap();
         self.draw_fields().set_stencil_state(StencilState::DrawingStencil(typ));
+    }
 
-        let res1 = stencil_fn(self);
+    /// Stops drawing into the stencil buffer.  Everything that is drawn
+    /// afterwards is masked by the stencil.
+    fn end_stencil(&mut self) {
         self.flush_draw().unwrap();
         self.draw_fields().set_stencil_state(StencilState::DrawingWithStencil);
-        res1
+    }
+
+    /// Saves the current matrix, scissor and stencil state on a stack.
+    ///
+    /// Every call should be matched by a call to `pop_state`, which puts
+    /// the saved state back.  Unlike `with_matrix` and friends this doesn't
+    /// take a closure, so it can be used from methods that already borrow
+    /// the rest of a game object.
+    ///
+    /// ```rust,no_run
+    ///# extern crate lux;
+    ///# use lux::prelude::*;
+    ///# fn main() {
+    ///# let mut window = Window::new_with_defaults().unwrap();
+    ///# let mut frame = window.cleared_frame(rgb(1.0, 1.0, 1.0));
+    /// frame.push_state();
+    /// frame.translate(100.0, 100.0);
+    /// frame.set_scissor(Some((0, 0, 200, 200)));
+    /// frame.draw(Square { size: 50.0, .. Default::default() }).unwrap();
+    /// frame.pop_state().unwrap();
+    ///# }
+    /// ```
+    fn push_state(&mut self) {
+        let fields = self.draw_fields();
+        let saved = SavedState {
+            matrix: *fields.matrix,
+            scissor: *fields.scissor,
+            stencil_state: *fields.stencil_state,
+        };
+        fields.state_stack.push(saved);
+    }
+
+    /// Puts back the state that was saved by the matching `push_state`.
+    ///
+    /// Returns `LuxError::UnbalancedStatePop` if there is no saved state
+    /// left.  The stencil buffer itself is not saved, only whether it is
+    /// being drawn to or drawn with.
+    fn pop_state(&mut self) -> LuxResult<()> {
+        let saved = match self.draw_fields().state_stack.pop() {
+            Some(saved) => saved,
+            None => return Err(LuxError::UnbalancedStatePop),
+        };
+
+        // Draws that are waiting to be batched have to be made with the
+        // scissor and stencil that they were drawn with.
+        let changed = {
+            let fields = self.draw_fields_ref();
+            *fields.scissor != saved.scissor || *fields.stencil_state != saved.stencil_state
+        };
+        if changed {
+            try!(self.flush_draw());
+        }
+
+        let fields = self.draw_fields();
+        *fields.matrix = saved.matrix;
+        *fields.scissor = saved.scissor;
+        *fields.stencil_state = saved.stencil_state;
+        Ok(())
+    }
+
+    /// Returns the number of states that have been pushed and not popped.
+    fn state_depth(&self) -> usize {
+        self.draw_fields_ref().state_stack.len()
+    }
+
+    /// Pushes the current state and returns a guard that pops it when it
+    /// goes out of scope.
+    ///
+    /// The guard derefs to the canvas, so it can be drawn to directly.  Any
+    /// states that were pushed through the guard and not popped are popped
+    /// along with it.
+    ///
+    /// ```rust,no_run
+    ///# extern crate lux;
+    ///# use lux::prelude::*;
+    ///# fn main() {
+    ///# let mut window = Window::new_with_defaults().unwrap();
+    ///# let mut frame = window.cleared_frame(rgb(1.0, 1.0, 1.0));
+    /// {
+    ///     let mut frame = frame.state_guard();
+    ///     frame.rotate(0.5);
+    ///     frame.draw(Square { size: 50.0, .. Default::default() }).unwrap();
+    /// }
+    /// // Not rotated anymore.
+    /// frame.draw(Square { size: 50.0, .. Default::default() }).unwrap();
+    ///# }
+    /// ```
+    fn state_guard(&mut self) -> StateGuard<Self> {
+        self.push_state();
+        let depth = self.state_depth();
+        StateGuard {
+            canvas: self,
+            depth: depth,
+        }
     }
 
     /// Clears the stencil buffer allowing all draws to go though.
@@ -126,6 +249,105 @@ pub trait Canvas: DrawLike + Sized {
     }
 }
 
+/// Restores the state of a canvas when it goes out of scope.
+///
+/// A `StateGuard` is returned by `Canvas::state_guard`.
+pub struct StateGuard<'a, C: Canvas + 'a> {
+    canvas: &'a mut C,
+    /// The depth of the state stack right after this guard pushed.
+    depth: usize,
+}
+
+impl <'a, C: Canvas + 'a> Deref for StateGuard<'a, C> {
+    type Target = C;
+    fn deref(&self) -> &C {
+        self.canvas
+    }
+}
+
+impl <'a, C: Canvas + 'a> DerefMut for StateGuard<'a, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.canvas
+    }
+}
+
+impl <'a, C: Canvas + 'a> Drop for StateGuard<'a, C> {
+    fn drop(&mut self) {
+        while self.canvas.state_depth() >= self.depth {
+            if self.canvas.pop_state().is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Determines which parts of a shape get drawn.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DrawStyle {
+    /// Only the inside of the shape is drawn.
+    Fill,
+    /// Only the outline of the shape is drawn.
+    Stroke,
+    /// The inside of the shape is drawn and then the outline is drawn
+    /// on top of it.
+    FillAndStroke,
+}
+
+impl DrawStyle {
+    /// Returns true if the inside of a shape is drawn with this style.
+    pub fn fills(&self) -> bool {
+        *self != DrawStyle::Stroke
+    }
+
+    /// Returns true if the outline of a shape is drawn with this style.
+    pub fn strokes(&self) -> bool {
+        *self != DrawStyle::Fill
+    }
+}
+
+/// Returns roughly how many pixels a unit covers when drawing on `canvas`
+/// with `transform`.
+pub fn pixel_scale<C: Canvas>(canvas: &C, transform: Option<[[Float; 4]; 4]>) -> Float {
+    let matrix = Affine2::from(*canvas.draw_fields_ref().matrix);
+    let m = match transform {
+        Some(t) => matrix * Affine2::from(t),
+        None => matrix,
+    };
+    // The matrix ends in normalized device coordinates, which span two
+    // units across the canvas.
+    let (w, h) = canvas.size();
+    let (sx, sy) = (m.transform_vector((1.0, 0.0)), m.transform_vector((0.0, 1.0)));
+    let sx = (sx.0 * w / 2.0).hypot(sx.1 * h / 2.0);
+    let sy = (sy.0 * w / 2.0).hypot(sy.1 * h / 2.0);
+    sx.max(sy)
+}
+
+/// Draws a convex polygon with an optional fill and an optional outline.
+fn draw_convex<C: Canvas>(canvas: &mut C, points: &[(Float, Float)], style: DrawStyle,
+                          color: [f32; 4], fill: Option<Fill>,
+                          stroke_width: Float, stroke_color: [f32; 4],
+                          dash: Option<Dash>, transform: Option<[[Float; 4]; 4]>) -> LuxResult<()> {
+    let mut vertices = vec![];
+    let mut idxs = vec![];
+
+    if style.fills() {
+        let start = (vertices.len(), idxs.len());
+        fill_convex(points, color, &mut vertices, &mut idxs);
+        if let Some(fill) = fill {
+            fill.paint(start, pixel_scale(canvas, transform), &mut vertices, &mut idxs);
+        }
+    }
+    if style.strokes() {
+        let stroke = StrokeStyle { dash: dash, .. StrokeStyle::new(stroke_width) };
+        stroke_polyline(points, true, &stroke, stroke_color, &mut vertices, &mut idxs);
+    }
+
+    if idxs.is_empty() {
+        return Ok(());
+    }
+    canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), transform)
+}
+
 #[derive(Copy, Clone, PartialEq)]
 pub struct Rectangle {
     pub x: Float,
@@ -133,6 +355,11 @@ pub struct Rectangle {
     pub w: Float,
     pub h: Float,
     pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
     pub transform: Option<[[Float; 4]; 4]>
 }
 
@@ -144,6 +371,11 @@ impl Default for Rectangle {
             w: 0.0,
             h: 0.0,
             color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
             transform: None
         }
     }
@@ -151,16 +383,28 @@ impl Default for Rectangle {
 
 impl Drawable for Rectangle {
     fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        let vertices = [
-            ColorVertex{ pos: [self.x + self.w, self.y], color: self.color },
-            ColorVertex{ pos: [self.x, self.y], color: self.color },
-            ColorVertex{ pos: [self.x, self.y + self.h], color: self.color },
-            ColorVertex{ pos: [self.x + self.w, self.y + self.h], color: self.color },
-        ];
+        if self.style == DrawStyle::Fill && self.fill.is_none() {
+            let vertices = [
+                ColorVertex{ pos: [self.x + self.w, self.y], color: self.color },
+                ColorVertex{ pos: [self.x, self.y], color: self.color },
+                ColorVertex{ pos: [self.x, self.y + self.h], color: self.color },
+                ColorVertex{ pos: [self.x + self.w, self.y + self.h], color: self.color },
+            ];
 
-        let idxs = [0, 1, 2, 0, 2, 3];
+            let idxs = [0, 1, 2, 0, 2, 3];
 
-        canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform)
+            return canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform);
+        }
+
+        let points = [
+            (self.x, self.y),
+            (self.x + self.w, self.y),
+            (self.x + self.w, self.y + self.h),
+            (self.x, self.y + self.h),
+        ];
+
+        draw_convex(canvas, &points[..], self.style, self.color, self.fill,
+                    self.stroke_width, self.stroke_color, self.dash, self.transform)
     }
 }
 
@@ -170,6 +414,11 @@ pub struct Square {
     pub y: Float,
     pub size: Float,
     pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
     pub transform: Option<[[Float; 4]; 4]>
 }
 
@@ -180,6 +429,11 @@ impl Default for Square {
             y: 0.0,
             size: 0.0,
             color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
             transform: None
         }
     }
@@ -187,7 +441,92 @@ impl Default for Square {
 
 impl Drawable for Square{
     fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        Rectangle {x: self.x, y: self.y, w: self.size, h: self.size, color: self.color, transform: self.transform}.draw(canvas)
+        Rectangle {
+            x: self.x, y: self.y,
+            w: self.size, h: self.size,
+            color: self.color,
+            fill: self.fill,
+            style: self.style,
+            stroke_width: self.stroke_width,
+            stroke_color: self.stroke_color,
+            dash: self.dash,
+            transform: self.transform
+        }.draw(canvas)
+    }
+}
+
+/// A rectangle with rounded corners.
+///
+/// The radii are given clockwise from the top left corner and are limited
+/// to half of the width and height.
+#[derive(Copy, Clone, PartialEq)]
+pub struct RoundedRect {
+    pub x: Float,
+    pub y: Float,
+    pub w: Float,
+    pub h: Float,
+    pub radii: [Float; 4],
+    pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub segments: Option<u32>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for RoundedRect {
+    fn default() -> RoundedRect {
+        RoundedRect {
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
+            radii: [0.0; 4],
+            color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            segments: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
+            transform: None
+        }
+    }
+}
+
+impl RoundedRect {
+    /// Returns the points around the edge of this rectangle.
+    fn points(&self) -> Vec<(Float, Float)> {
+        let max_radius = (self.w.abs().min(self.h.abs()) / 2.0).max(0.0);
+        let (left, top, right, bottom) = (self.x, self.y, self.x + self.w, self.y + self.h);
+        let corners = [
+            (left, top, 1.0, 1.0, PI),
+            (right, top, -1.0, 1.0, 1.5 * PI),
+            (right, bottom, -1.0, -1.0, 0.0),
+            (left, bottom, 1.0, -1.0, 0.5 * PI),
+        ];
+
+        let mut points = vec![];
+        for (&(cx, cy, dx, dy, start), &radius) in corners.iter().zip(self.radii.iter()) {
+            let radius = radius.max(0.0).min(max_radius);
+            if radius == 0.0 {
+                points.push((cx, cy));
+            } else {
+                let center = (cx + dx * radius, cy + dy * radius);
+                points.extend(arc_between(center, radius, start, start + 0.5 * PI, self.segments));
+            }
+        }
+        points
+    }
+}
+
+impl Drawable for RoundedRect {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let points = self.points();
+        draw_convex(canvas, &points[..], self.style, self.color, self.fill,
+                    self.stroke_width, self.stroke_color, self.dash, self.transform)
     }
 }
 
@@ -198,7 +537,12 @@ pub struct Ellipse {
     pub w: Float,
     pub h: Float,
     pub color: [f32; 4],
+    pub fill: Option<Fill>,
     pub segments: Option<u32>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
     pub transform: Option<[[Float; 4]; 4]>
 }
 
@@ -211,25 +555,52 @@ impl Default for Ellipse {
             w: 0.0,
             h: 0.0,
             color: rgb(0.0, 0.0, 0.0),
+            fill: None,
             segments: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
             transform: None,
         }
     }
 }
 
-impl Drawable for Ellipse {
-    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        const OPT_LINE_LENGTH: u16 = 15;
+/// The length that the segments of curved shapes aim for when no segment
+/// count is given.
+const OPT_LINE_LENGTH: u16 = 15;
 
-        use std::f32::consts::PI;
+/// Returns how many segments an arc of `sweep` radians should be divided
+/// into.  `segments` is the number of segments in a full circle; if it is
+/// `None`, segments of about `OPT_LINE_LENGTH` are used.
+fn arc_segments(segments: Option<u32>, radius: Float, sweep: Float) -> u32 {
+    let full = match segments {
+        Some(segment_count) => segment_count as Float,
+        None => (2.0 * PI * radius) / OPT_LINE_LENGTH as Float,
+    };
+    (full * sweep.abs() / (2.0 * PI)).ceil().max(1.0) as u32
+}
 
+/// Returns the points along an arc around `center`, including both ends.
+fn arc_between(center: (Float, Float), radius: Float, start_angle: Float, end_angle: Float,
+               segments: Option<u32>) -> Vec<(Float, Float)> {
+    let sweep = end_angle - start_angle;
+    let count = arc_segments(segments, radius, sweep);
+    (0 .. count + 1).map(|i| {
+        let theta = start_angle + sweep * (i as Float / count as Float);
+        (center.0 + radius * theta.cos(), center.1 + radius * theta.sin())
+    }).collect()
+}
+
+impl Ellipse {
+    /// Returns the points around the edge of this ellipse.
+    fn points(&self) -> Vec<(Float, Float)> {
         let largest_radius = self.w.max(self.h);
-        let delta_theta = self.segments.map(|segment_count| (2.0 * PI) / (segment_count as Float))
-                                       .unwrap_or(OPT_LINE_LENGTH as f32 / largest_radius);
-        let mut vertices = vec![];
+        let segments = arc_segments(self.segments, largest_radius, 2.0 * PI).max(3);
+        let delta_theta = (2.0 * PI) / (segments as Float);
 
-        let mut theta = 0.0;
-        while theta <= 2.0 * PI {
+        (0 .. segments).map(|i| {
+            let theta = delta_theta * i as Float;
             let (mut x, mut y) = (theta.sin(), theta.cos());
             x *= self.w / 2.0;
             x += self.w;
@@ -237,11 +608,16 @@ impl Drawable for Ellipse {
             y *= self.h / 2.0;
             y += self.h;
 
-            vertices.push(ColorVertex { pos: [x + self.x, y + self.y], color: self.color });
-            theta += delta_theta;
-        }
+            (x + self.x, y + self.y)
+        }).collect()
+    }
+}
 
-        canvas.draw_colored(TriangleFan, &vertices[..], None, self.transform)
+impl Drawable for Ellipse {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let points = self.points();
+        draw_convex(canvas, &points[..], self.style, self.color, self.fill,
+                    self.stroke_width, self.stroke_color, self.dash, self.transform)
     }
 }
 
@@ -251,7 +627,12 @@ pub struct Circle {
     pub y: Float,
     pub diameter: Float,
     pub color: [f32; 4],
+    pub fill: Option<Fill>,
     pub segments: Option<u32>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
     pub transform: Option<[[Float; 4]; 4]>
 }
 
@@ -263,7 +644,12 @@ impl Default for Circle {
             y: 0.0,
             diameter: 0.0,
             color: rgb(0.0, 0.0, 0.0),
+            fill: None,
             segments: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
             transform: None,
         }
     }
@@ -271,16 +657,315 @@ impl Default for Circle {
 
 impl Drawable for Circle {
     fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        Ellipse { x: self.x, y: self.y, w: self.diameter, h: self.diameter, color: self.color, segments: self.segments, transform: self.transform }.draw(canvas)
+        Ellipse {
+            x: self.x, y: self.y,
+            w: self.diameter, h: self.diameter,
+            color: self.color,
+            fill: self.fill,
+            segments: self.segments,
+            style: self.style,
+            stroke_width: self.stroke_width,
+            stroke_color: self.stroke_color,
+            dash: self.dash,
+            transform: self.transform
+        }.draw(canvas)
+    }
+}
+
+/// An open arc around the center point (x, y).
+///
+/// Angles are in radians, starting at the positive x axis and turning
+/// clockwise on the screen.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Arc {
+    pub x: Float,
+    pub y: Float,
+    pub radius: Float,
+    pub start_angle: Float,
+    pub end_angle: Float,
+    pub thickness: Float,
+    pub color: [f32; 4],
+    pub cap: LineCap,
+    pub segments: Option<u32>,
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for Arc {
+    fn default() -> Arc {
+        Arc {
+            x: 0.0,
+            y: 0.0,
+            radius: 0.0,
+            start_angle: 0.0,
+            end_angle: PI,
+            thickness: 1.0,
+            color: rgb(0.0, 0.0, 0.0),
+            cap: LineCap::Butt,
+            segments: None,
+            dash: None,
+            transform: None,
+        }
+    }
+}
+
+impl Drawable for Arc {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let points = arc_between((self.x, self.y), self.radius,
+                                 self.start_angle, self.end_angle, self.segments);
+        Polyline {
+            points: &points[..],
+            thickness: self.thickness,
+            color: self.color,
+            cap: self.cap,
+            dash: self.dash,
+            transform: self.transform,
+            .. Default::default()
+        }.draw(canvas)
+    }
+}
+
+/// A slice of a circle around the center point (x, y).
+///
+/// Angles are in radians, starting at the positive x axis and turning
+/// clockwise on the screen.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Pie {
+    pub x: Float,
+    pub y: Float,
+    pub radius: Float,
+    pub start_angle: Float,
+    pub end_angle: Float,
+    pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub segments: Option<u32>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for Pie {
+    fn default() -> Pie {
+        Pie {
+            x: 0.0,
+            y: 0.0,
+            radius: 0.0,
+            start_angle: 0.0,
+            end_angle: 0.5 * PI,
+            color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            segments: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
+            transform: None,
+        }
+    }
+}
+
+impl Drawable for Pie {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        // Every triangle fans out from the center, so slices that are more
+        // than half of the circle still fill correctly.
+        let mut points = vec![(self.x, self.y)];
+        points.extend(arc_between((self.x, self.y), self.radius,
+                                  self.start_angle, self.end_angle, self.segments));
+        draw_convex(canvas, &points[..], self.style, self.color, self.fill,
+                    self.stroke_width, self.stroke_color, self.dash, self.transform)
+    }
+}
+
+/// A ring around the center point (x, y), or a section of one.
+///
+/// Angles are in radians, starting at the positive x axis and turning
+/// clockwise on the screen.  The default angles draw the whole ring.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Ring {
+    pub x: Float,
+    pub y: Float,
+    pub inner_radius: Float,
+    pub outer_radius: Float,
+    pub start_angle: Float,
+    pub end_angle: Float,
+    pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub segments: Option<u32>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for Ring {
+    fn default() -> Ring {
+        Ring {
+            x: 0.0,
+            y: 0.0,
+            inner_radius: 0.0,
+            outer_radius: 0.0,
+            start_angle: 0.0,
+            end_angle: 2.0 * PI,
+            color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            segments: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
+            transform: None,
+        }
+    }
+}
+
+impl Drawable for Ring {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let center = (self.x, self.y);
+        let sweep = self.end_angle - self.start_angle;
+        let count = arc_segments(self.segments, self.outer_radius, sweep);
+        let point = |radius: Float, i: u32| {
+            let theta = self.start_angle + sweep * (i as Float / count as Float);
+            (center.0 + radius * theta.cos(), center.1 + radius * theta.sin())
+        };
+        let outer: Vec<_> = (0 .. count + 1).map(|i| point(self.outer_radius, i)).collect();
+        let inner: Vec<_> = (0 .. count + 1).map(|i| point(self.inner_radius, i)).collect();
+
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+
+        if self.style.fills() {
+            let start = (vertices.len(), idxs.len());
+            for (&o, &i) in outer.iter().zip(inner.iter()) {
+                vertices.push(ColorVertex { pos: [o.0, o.1], color: self.color });
+                vertices.push(ColorVertex { pos: [i.0, i.1], color: self.color });
+            }
+            for k in 0 .. count as Idx {
+                let (o1, i1, o2, i2) = (k * 2, k * 2 + 1, k * 2 + 2, k * 2 + 3);
+                idxs.extend_from_slice(&[o1, i1, o2, o2, i1, i2]);
+            }
+            if let Some(fill) = self.fill {
+                fill.paint(start, pixel_scale(canvas, self.transform), &mut vertices, &mut idxs);
+            }
+        }
+
+        if self.style.strokes() {
+            let stroke = StrokeStyle { dash: self.dash, .. StrokeStyle::new(self.stroke_width) };
+            if sweep.abs() >= 2.0 * PI {
+                stroke_polyline(&outer[..], true, &stroke, self.stroke_color, &mut vertices, &mut idxs);
+                stroke_polyline(&inner[..], true, &stroke, self.stroke_color, &mut vertices, &mut idxs);
+            } else {
+                let mut outline = outer.clone();
+                outline.extend(inner.iter().rev().cloned());
+                stroke_polyline(&outline[..], true, &stroke, self.stroke_color, &mut vertices, &mut idxs);
+            }
+        }
+
+        if idxs.is_empty() {
+            return Ok(());
+        }
+        canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform)
     }
 }
 
+/// A polygon that may be concave, may intersect itself, and may have holes.
+///
+/// With `FillRule::NonZero` the holes are cut out no matter which direction
+/// they wind in.
+#[derive(Copy, Clone)]
+pub struct Polygon<'a> {
+    pub points: &'a [(Float, Float)],
+    pub holes: &'a [&'a [(Float, Float)]],
+    pub fill_rule: FillRule,
+    pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub style: DrawStyle,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for Polygon<'static> {
+    fn default() -> Polygon<'static> {
+        Polygon {
+            points: &[],
+            holes: &[],
+            fill_rule: FillRule::NonZero,
+            color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            style: DrawStyle::Fill,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            dash: None,
+            transform: None,
+        }
+    }
+}
+
+impl <'a> Drawable for Polygon<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+
+        if self.style.fills() {
+            let start = (vertices.len(), idxs.len());
+            let outer_area = signed_area(self.points);
+            let mut contours = vec![self.points.to_vec()];
+            for hole in self.holes {
+                let mut hole = hole.to_vec();
+                // Holes have to wind against the outline to be cut out.
+                if self.fill_rule == FillRule::NonZero &&
+                   (signed_area(&hole[..]) > 0.0) == (outer_area > 0.0) {
+                    hole.reverse();
+                }
+                contours.push(hole);
+            }
+            fill_contours(&contours[..], self.fill_rule, self.color, &mut vertices, &mut idxs);
+            if let Some(fill) = self.fill {
+                fill.paint(start, pixel_scale(canvas, self.transform), &mut vertices, &mut idxs);
+            }
+        }
+
+        if self.style.strokes() {
+            let stroke = StrokeStyle { dash: self.dash, .. StrokeStyle::new(self.stroke_width) };
+            stroke_polyline(self.points, true, &stroke, self.stroke_color,
+                            &mut vertices, &mut idxs);
+            for hole in self.holes {
+                stroke_polyline(hole, true, &stroke, self.stroke_color,
+                                &mut vertices, &mut idxs);
+            }
+        }
+
+        if idxs.is_empty() {
+            return Ok(());
+        }
+        canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform)
+    }
+}
+
+/// Draws a sprite.
+///
+/// `origin` is the point on the picture that is placed at `x` and `y`, and
+/// that the picture is rotated and scaled around.  It is relative to the
+/// size of the picture, so `(0.5, 0.5)` is the center.  When it is `None`,
+/// the sprite's pivot is used, and if that is also `None` the top left
+/// corner is used.
+///
+/// `flip_x` and `flip_y` mirror the sprite without moving it.
 #[derive(Copy, Clone)]
 pub struct Picture<'a> {
     pub sprite: Option<&'a Sprite>,
     pub x: Float,
     pub y: Float,
     pub size: Option<(Float, Float)>,
+    pub origin: Option<(Float, Float)>,
+    pub rotation: Float,
+    pub scale: (Float, Float),
+    pub flip_x: bool,
+    pub flip_y: bool,
     pub color: [f32; 4],
     pub transform: Option<[[Float; 4]; 4]>,
 }
@@ -292,6 +977,11 @@ impl Default for Picture<'static> {
             x: 0.0,
             y: 0.0,
             size: None,
+            origin: None,
+            rotation: 0.0,
+            scale: (1.0, 1.0),
+            flip_x: false,
+            flip_y: false,
             color: [1.0, 1.0, 1.0, 1.0],
             transform: None,
         }
@@ -307,22 +997,35 @@ impl <'a> Drawable for Picture<'a> {
 
         let bounds = sprite.bounds();
 
-        let top_left = bounds[0];
-        let top_right = bounds[1];
-        let bottom_left = bounds[2];
-        let bottom_right = bounds[3];
+        let (left, right) = if self.flip_x { (1, 0) } else { (0, 1) };
+        let (top, bottom) = if self.flip_y { (2, 0) } else { (0, 2) };
+
+        let top_left = bounds[top + left];
+        let top_right = bounds[top + right];
+        let bottom_left = bounds[bottom + left];
+        let bottom_right = bounds[bottom + right];
 
         let (w, h) = match self.size {
             Some((w, h)) => (w, h),
             None => (sprite.width(), sprite.height()),
         };
+        let (ox, oy) = self.origin.or(sprite.pivot()).unwrap_or((0.0, 0.0));
+        let (sx, sy) = self.scale;
+
+        let (x0, y0) = (-ox * w * sx, -oy * h * sy);
+        let (x1, y1) = ((1.0 - ox) * w * sx, (1.0 - oy) * h * sy);
+
+        // Rotating the corners here saves building a matrix for every
+        // rotated picture.
+        let (c, s) = (self.rotation.cos(), self.rotation.sin());
         let (x, y) = (self.x, self.y);
+        let corner = |px: Float, py: Float| [x + px * c - py * s, y + px * s + py * c];
 
         let tex_vs = vec![
-            TexVertex {pos: [x + w, y], tex_coords: top_right},
-            TexVertex {pos: [x, y], tex_coords: top_left},
-            TexVertex {pos: [x, y + h], tex_coords: bottom_left},
-            TexVertex {pos: [x + w, y + h], tex_coords: bottom_right},
+            TexVertex {pos: corner(x1, y0), tex_coords: top_right, color: self.color},
+            TexVertex {pos: corner(x0, y0), tex_coords: top_left, color: self.color},
+            TexVertex {pos: corner(x0, y1), tex_coords: bottom_left, color: self.color},
+            TexVertex {pos: corner(x1, y1), tex_coords: bottom_right, color: self.color},
         ];
 
         let idxs = [0, 1, 2, 0, 2, 3];
@@ -333,7 +1036,268 @@ impl <'a> Drawable for Picture<'a> {
             Some(&idxs[..]),
             self.transform,
             sprite.texture(),
-            Some(self.color))
+            None,
+            Some(sprite.sampler()))
+    }
+}
+
+/// Draws a sprite as a panel that can be resized without stretching its
+/// borders.
+///
+/// `insets` are the widths of the left, top, right and bottom borders in
+/// pixels of the sprite.  The corners are drawn at their original size, the
+/// edges are stretched along the sides, and the center fills the rest of
+/// the panel.  When `tile` is true the edges and the center are repeated
+/// at the size of the sprite instead of stretched, unless that would take
+/// more than 1024 copies along a side.
+#[derive(Copy, Clone)]
+pub struct NineSlice<'a> {
+    pub sprite: Option<&'a Sprite>,
+    pub x: Float,
+    pub y: Float,
+    pub w: Float,
+    pub h: Float,
+    pub insets: (u32, u32, u32, u32),
+    pub tile: bool,
+    pub color: [f32; 4],
+    pub transform: Option<[[Float; 4]; 4]>,
+}
+
+impl Default for NineSlice<'static> {
+    fn default() -> NineSlice<'static> {
+        NineSlice {
+            sprite: None,
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
+            insets: (0, 0, 0, 0),
+            tile: false,
+            color: [1.0, 1.0, 1.0, 1.0],
+            transform: None,
+        }
+    }
+}
+
+/// Adds a quad that covers `pos` (left, top, right, bottom) and is textured
+/// with the `tex` (left, top, right, bottom) area of a texture.
+fn push_tex_quad(vertices: &mut Vec<TexVertex>, idxs: &mut Vec<Idx>,
+                 pos: (Float, Float, Float, Float), tex: (Float, Float, Float, Float),
+                 color: [f32; 4]) {
+    let base = vertices.len() as Idx;
+    vertices.push(TexVertex { pos: [pos.2, pos.1], tex_coords: [tex.2, tex.1], color: color });
+    vertices.push(TexVertex { pos: [pos.0, pos.1], tex_coords: [tex.0, tex.1], color: color });
+    vertices.push(TexVertex { pos: [pos.0, pos.3], tex_coords: [tex.0, tex.3], color: color });
+    vertices.push(TexVertex { pos: [pos.2, pos.3], tex_coords: [tex.2, tex.3], color: color });
+    idxs.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+}
+
+/// The most copies that a tiled `NineSlice` repeats a piece along one
+/// side.  Pieces that would need more are stretched instead.
+const MAX_NINE_SLICE_TILES: Float = 1024.0;
+
+/// Returns how far apart the copies of a `NineSlice` piece that is `src`
+/// pixels of the sprite and `dst` pixels on the panel are placed.  Pieces
+/// that are stretched are a single copy `dst` long.
+fn nine_slice_step(tile: bool, src: u32, dst: Float) -> Float {
+    let step = src as Float;
+    if tile && step > 0.0 && dst / step <= MAX_NINE_SLICE_TILES {
+        step
+    } else {
+        dst
+    }
+}
+
+impl <'a> Drawable for NineSlice<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let sprite = match self.sprite {
+            Some(sprite) => sprite,
+            None => return Ok(()),
+        };
+
+        let (sw, sh) = (sprite.width() as u32, sprite.height() as u32);
+        let (left, top, right, bottom) = self.insets;
+        let left = left.min(sw);
+        let right = right.min(sw - left);
+        let top = top.min(sh);
+        let bottom = bottom.min(sh - top);
+
+        // Shrink the borders if the panel is too small to fit them.
+        let border_scale = |a: u32, b: u32, space: Float| {
+            let total = (a + b) as Float;
+            if total > space && total > 0.0 { space.max(0.0) / total } else { 1.0 }
+        };
+        let scale_x = border_scale(left, right, self.w);
+        let scale_y = border_scale(top, bottom, self.h);
+
+        let (l, r) = (left as Float * scale_x, right as Float * scale_x);
+        let (t, b) = (top as Float * scale_y, bottom as Float * scale_y);
+        let (x, y, w, h) = (self.x, self.y, self.w, self.h);
+
+        // (source offset, source size, destination start, destination size)
+        let cols = [(0, left, x, l),
+                    (left, sw - left - right, x + l, w - l - r),
+                    (sw - right, right, x + w - r, r)];
+        let rows = [(0, top, y, t),
+                    (top, sh - top - bottom, y + t, h - t - b),
+                    (sh - bottom, bottom, y + h - b, b)];
+
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+        for (row, &(src_y, src_h, dst_y, dst_h)) in rows.iter().enumerate() {
+            for (col, &(src_x, src_w, dst_x, dst_w)) in cols.iter().enumerate() {
+                if src_w == 0 || src_h == 0 || dst_w <= 0.0 || dst_h <= 0.0 {
+                    continue;
+                }
+                let region = match sprite.sub_sprite((src_x, src_y), (src_w, src_h)) {
+                    Some(region) => region,
+                    None => continue,
+                };
+                let bounds = region.bounds();
+                let (u0, v0, u1, v1) = (bounds[0][0], bounds[0][1], bounds[3][0], bounds[3][1]);
+
+                // Corners are never repeated.
+                let tile_x = self.tile && col == 1;
+                let tile_y = self.tile && row == 1;
+                let step_x = nine_slice_step(tile_x, src_w, dst_w);
+                let step_y = nine_slice_step(tile_y, src_h, dst_h);
+
+                let mut ty = 0.0;
+                while ty < dst_h {
+                    let th = step_y.min(dst_h - ty);
+                    let fv = th / step_y;
+                    let mut tx = 0.0;
+                    while tx < dst_w {
+                        let tw = step_x.min(dst_w - tx);
+                        let fu = tw / step_x;
+                        push_tex_quad(&mut vertices, &mut idxs,
+                                      (dst_x + tx, dst_y + ty, dst_x + tx + tw, dst_y + ty + th),
+                                      (u0, v0, u0 + (u1 - u0) * fu, v0 + (v1 - v0) * fv),
+                                      self.color);
+                        tx += step_x;
+                    }
+                    ty += step_y;
+                }
+            }
+        }
+
+        if idxs.is_empty() {
+            return Ok(());
+        }
+        canvas.draw_tex(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform,
+                        sprite.texture(), None, Some(sprite.sampler()))
+    }
+}
+
+/// Fills a rectangle by repeating a sprite.
+///
+/// `tile_size` is the size of one copy of the sprite, and defaults to the
+/// size of the sprite.  `uv_offset` shifts the tiles by a fraction of a
+/// tile and `uv_scale` stretches them, so animating either scrolls or zooms
+/// the pattern.
+///
+/// A sprite that covers its whole texture is drawn as a single quad that
+/// repeats the texture.  Sub-sprites, such as the cells of a sprite sheet,
+/// are drawn one tile at a time.
+#[derive(Copy, Clone)]
+pub struct TiledPicture<'a> {
+    pub sprite: Option<&'a Sprite>,
+    pub x: Float,
+    pub y: Float,
+    pub w: Float,
+    pub h: Float,
+    pub tile_size: Option<(Float, Float)>,
+    pub uv_offset: (Float, Float),
+    pub uv_scale: (Float, Float),
+    pub color: [f32; 4],
+    pub transform: Option<[[Float; 4]; 4]>,
+}
+
+impl Default for TiledPicture<'static> {
+    fn default() -> TiledPicture<'static> {
+        TiledPicture {
+            sprite: None,
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
+            tile_size: None,
+            uv_offset: (0.0, 0.0),
+            uv_scale: (1.0, 1.0),
+            color: [1.0, 1.0, 1.0, 1.0],
+            transform: None,
+        }
+    }
+}
+
+/// Cuts the span from `start` to `start + len` at every tile boundary.
+///
+/// Returns the start and end of every piece along with how far into a
+/// tile, from 0.0 to 1.0, each end is.
+fn tile_span(start: Float, len: Float, tile: Float, offset: Float)
+-> Vec<(Float, Float, Float, Float)> {
+    let offset = offset - offset.floor();
+    let end = offset + len / tile;
+    let mut pieces = vec![];
+    let mut k = 0.0;
+    while k < end {
+        let s0 = offset.max(k);
+        let s1 = end.min(k + 1.0);
+        if s1 > s0 {
+            pieces.push((start + (s0 - offset) * tile, start + (s1 - offset) * tile,
+                         s0 - k, s1 - k));
+        }
+        k += 1.0;
+    }
+    pieces
+}
+
+impl <'a> Drawable for TiledPicture<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        use super::gfx_integration::Wrap;
+
+        let sprite = match self.sprite {
+            Some(sprite) => sprite,
+            None => return Ok(()),
+        };
+
+        let (tw, th) = self.tile_size.unwrap_or(sprite.size());
+        let tile_w = tw * self.uv_scale.0;
+        let tile_h = th * self.uv_scale.1;
+        if self.w <= 0.0 || self.h <= 0.0 || tile_w <= 0.0 || tile_h <= 0.0 {
+            return Ok(());
+        }
+
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+
+        if sprite.is_whole_texture() {
+            let sampler = Sampler { wrap: Wrap::Repeat, .. sprite.sampler() };
+            let (u0, v0) = self.uv_offset;
+            let (u1, v1) = (u0 + self.w / tile_w, v0 + self.h / tile_h);
+            push_tex_quad(&mut vertices, &mut idxs,
+                          (self.x, self.y, self.x + self.w, self.y + self.h),
+                          (u0, v0, u1, v1), self.color);
+            return canvas.draw_tex(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform,
+                                   sprite.texture(), None, Some(sampler));
+        }
+
+        let bounds = sprite.bounds();
+        let (u0, v0, u1, v1) = (bounds[0][0], bounds[0][1], bounds[3][0], bounds[3][1]);
+        let columns = tile_span(self.x, self.w, tile_w, self.uv_offset.0);
+        let rows = tile_span(self.y, self.h, tile_h, self.uv_offset.1);
+
+        for &(y0, y1, fy0, fy1) in &rows {
+            for &(x0, x1, fx0, fx1) in &columns {
+                push_tex_quad(&mut vertices, &mut idxs, (x0, y0, x1, y1),
+                              (u0 + (u1 - u0) * fx0, v0 + (v1 - v0) * fy0,
+                               u0 + (u1 - u0) * fx1, v0 + (v1 - v0) * fy1),
+                              self.color);
+            }
+        }
+
+        canvas.draw_tex(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform,
+                        sprite.texture(), None, Some(sprite.sampler()))
     }
 }
 
@@ -343,6 +1307,7 @@ pub struct Line {
     pub end: (Float, Float),
     pub thickness: Float,
     pub color: [Float; 4],
+    pub dash: Option<Dash>,
     pub transform: Option<[[Float; 4]; 4]>
 }
 
@@ -353,6 +1318,7 @@ impl Default for Line {
             end: (0.0, 0.0),
             thickness: 1.0,
             color: rgb(0.0, 0.0, 0.0),
+            dash: None,
             transform: None,
         }
     }
@@ -360,21 +1326,67 @@ impl Default for Line {
 
 impl Drawable for Line {
     fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        let (x1, y1) = self.start;
-        let (x2, y2) = self.end;
+        let points = [self.start, self.end];
+        Polyline {
+            points: &points[..],
+            thickness: self.thickness,
+            color: self.color,
+            dash: self.dash,
+            transform: self.transform,
+            .. Default::default()
+        }.draw(canvas)
+    }
+}
 
-        let dx = x2 - x1;
-        let dy = y2 - y1;
-        let dist = (dx * dx + dy * dy).sqrt();
-        let angle = dy.atan2(dx);
+/// A line through any number of points that is drawn as one continuous
+/// stroke.
+#[derive(Copy, Clone)]
+pub struct Polyline<'a> {
+    pub points: &'a [(Float, Float)],
+    pub closed: bool,
+    pub thickness: Float,
+    pub color: [Float; 4],
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: Float,
+    pub dash: Option<Dash>,
+    pub transform: Option<[[Float; 4]; 4]>
+}
 
-        let mut transformation = vecmath::mat4_id();
-        transformation.translate(x1, y1).rotate(angle).translate(0.0, - self.thickness / 2.0);
-        if let Some(other_trans) = self.transform {
-            transformation.apply_matrix(other_trans);
+impl Default for Polyline<'static> {
+    fn default() -> Polyline<'static> {
+        Polyline {
+            points: &[],
+            closed: false,
+            thickness: 1.0,
+            color: rgb(0.0, 0.0, 0.0),
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash: None,
+            transform: None,
         }
+    }
+}
 
-        Rectangle { x: x1, y: y1, w: dist, h: self.thickness, transform: Some(transformation), color: self.color}.draw(canvas)
+impl <'a> Drawable for Polyline<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let stroke = StrokeStyle {
+            width: self.thickness,
+            join: self.join,
+            cap: self.cap,
+            miter_limit: self.miter_limit,
+            dash: self.dash,
+        };
+
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+        stroke_polyline(self.points, self.closed, &stroke, self.color, &mut vertices, &mut idxs);
+
+        if idxs.is_empty() {
+            return Ok(());
+        }
+        canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform)
     }
 }
 
@@ -395,12 +1407,107 @@ impl Default for Pixels<'static> {
 
 impl <'a> Drawable for Pixels<'a> {
     fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
-        let mut transf = vecmath::mat4_id();
-        transf.translate(0.5, 0.5); // Correctly align
+        let mut transf = Affine2::translation(0.5, 0.5); // Correctly align
         if let Some(other_trans) = self.transform {
-            transf.apply_matrix(other_trans);
+            transf = transf * Affine2::from(other_trans);
+        }
+
+        canvas.draw_colored(Points, self.pixels, None, Some(transf.into()))
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Text<'a> {
+    pub text: &'a str,
+    pub x: Float,
+    pub y: Float,
+    pub font: &'a str,
+    pub size: u32,
+    pub color: [f32; 4],
+    pub max_width: Option<Float>,
+    pub align: HorizontalAlign,
+    pub vertical_align: VerticalAlign,
+    pub line_spacing: Float,
+    pub transform: Option<[[Float; 4]; 4]>
+}
+
+impl Default for Text<'static> {
+    fn default() -> Text<'static> {
+        Text {
+            text: "",
+            x: 0.0,
+            y: 0.0,
+            font: "SourceCodePro",
+            size: 20,
+            color: rgb(0.0, 0.0, 0.0),
+            max_width: None,
+            align: HorizontalAlign::Left,
+            vertical_align: VerticalAlign::Top,
+            line_spacing: 1.0,
+            transform: None,
+        }
+    }
+}
+
+impl <'a> Drawable for Text<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        try!(ensure_face(canvas, self.font, self.size));
+
+        let font_cache = canvas.draw_fields_ref().font_cache.clone();
+        let baked_fonts = canvas.draw_fields_ref().baked_fonts.clone();
+        let font_cache = font_cache.borrow();
+        let baked_fonts = baked_fonts.borrow();
+        let text_layout = try!(layout(&*font_cache, &*baked_fonts, &self));
+
+        let idxs = [0, 1, 2, 0, 2, 3];
+
+        for line in &text_layout.lines {
+            let glyphs = try!(place_glyphs(&*font_cache, &*baked_fonts, self.font, self.size, &line.text));
+
+            // Every glyph in a face shares the same atlas texture, so draw_tex
+            // will batch all of these quads into a single draw call.
+            for (glyph, gx, gy) in glyphs {
+                let bounds = glyph.bounds();
+                let (w, h) = glyph.size();
+                let (x, y) = (line.x + gx, line.y + gy);
+
+                let tex_vs = [
+                    TexVertex {pos: [x + w, y], tex_coords: bounds[1], color: self.color},
+                    TexVertex {pos: [x, y], tex_coords: bounds[0], color: self.color},
+                    TexVertex {pos: [x, y + h], tex_coords: bounds[2], color: self.color},
+                    TexVertex {pos: [x + w, y + h], tex_coords: bounds[3], color: self.color},
+                ];
+
+                try!(canvas.draw_tex(
+                    TrianglesList,
+                    &tex_vs[..],
+                    Some(&idxs[..]),
+                    self.transform,
+                    glyph.texture(),
+                    None,
+                    Some(glyph.sampler())));
+            }
         }
 
-        canvas.draw_colored(Points, self.pixels, None, Some(transf))
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{nine_slice_step, MAX_NINE_SLICE_TILES};
+
+    #[test]
+    fn nine_slices_tile_at_the_sprite_size() {
+        assert_eq!(nine_slice_step(true, 16, 100.0), 16.0);
+        assert_eq!(nine_slice_step(false, 16, 100.0), 100.0);
+    }
+
+    #[test]
+    fn nine_slices_stretch_pieces_that_need_too_many_tiles() {
+        assert_eq!(nine_slice_step(true, 0, 100.0), 100.0);
+        assert_eq!(nine_slice_step(true, 1, MAX_NINE_SLICE_TILES), 1.0);
+        assert_eq!(nine_slice_step(true, 1, MAX_NINE_SLICE_TILES + 1.0), MAX_NINE_SLICE_TILES + 1.0);
+        assert_eq!(nine_slice_step(true, 2, 1e9), 1e9);
     }
 }
@@ -6,6 +6,8 @@ use std::convert::From;
 use std;
 
 use glium;
+use bincode::rustc_serialize::{DecodingError, EncodingError};
+use rustc_serialize::json::ParserError;
 
 /// A result returning either a value or a lux-generated error.
 pub type LuxResult<A> = Result<A, LuxError>;
@@ -40,6 +42,14 @@ pub enum LuxError {
     IndexBufferCreationError,
     /// An error creating an vertex buffer occured
     VertexBufferCreationError,
+    /// An error that occured while reading or writing serialized data.
+    SerializationError(String),
+    /// An error that can occur when an image doesn't fit into a page of a
+    /// texture atlas.
+    AtlasError(String),
+    /// An error that occurs when `pop_state` is called more times than
+    /// `push_state`.
+    UnbalancedStatePop,
 }
 
 impl Error for LuxError {
@@ -59,6 +69,9 @@ impl Error for LuxError {
             &LuxError::TextureCreationError(_) => "",
             &LuxError::IndexBufferCreationError => "An index buffer could not be created",
             &LuxError::VertexBufferCreationError => "A vertex buffer could not be created",
+            &LuxError::SerializationError(ref s) => &s[..],
+            &LuxError::AtlasError(ref s) => &s[..],
+            &LuxError::UnbalancedStatePop => "pop_state was called without a matching push_state",
         }
     }
 }
@@ -125,6 +138,24 @@ impl From<glium::index::BufferCreationError> for LuxError {
     }
 }
 
+impl From<DecodingError> for LuxError {
+    fn from(e: DecodingError) -> LuxError {
+        LuxError::SerializationError(format!("{}", e))
+    }
+}
+
+impl From<EncodingError> for LuxError {
+    fn from(e: EncodingError) -> LuxError {
+        LuxError::SerializationError(format!("{}", e))
+    }
+}
+
+impl From<ParserError> for LuxError {
+    fn from(e: ParserError) -> LuxError {
+        LuxError::SerializationError(format!("{}", e))
+    }
+}
+
 impl std::fmt::Display for LuxError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         match self {
@@ -141,6 +172,10 @@ impl std::fmt::Display for LuxError {
             &LuxError::TextureCreationError(ref e) => std::fmt::Debug::fmt(&e, f),
             &LuxError::IndexBufferCreationError => "An index buffer could not be created".fmt(f),
             &LuxError::VertexBufferCreationError => "A vertex buffer could not be created".fmt(f),
+            &LuxError::SerializationError(ref s) => s.fmt(f),
+            &LuxError::AtlasError(ref s) => s.fmt(f),
+            &LuxError::UnbalancedStatePop =>
+                "pop_state was called without a matching push_state".fmt(f),
         }
     }
 }
@@ -0,0 +1,354 @@
+use super::gfx_integration::ColorVertex;
+use super::types::{Float, Idx};
+
+type Point = (Float, Float);
+
+/// The most stops that a `Gradient` can hold.
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+/// The smallest that the pieces of a radial gradient get on screen, in
+/// pixels.
+const MIN_RADIAL_PIECE: Float = 4.0;
+
+/// A list of colors at positions between 0.0 and 1.0 that are blended
+/// together.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Gradient {
+    stops: [(Float, [f32; 4]); MAX_GRADIENT_STOPS],
+    count: usize,
+}
+
+impl Gradient {
+    /// Creates a gradient out of `(position, color)` stops.
+    ///
+    /// The stops do not need to be in order.  Positions are clamped to
+    /// the range 0.0 to 1.0, and only the first `MAX_GRADIENT_STOPS` stops
+    /// are used.
+    pub fn new(stops: &[(Float, [f32; 4])]) -> Gradient {
+        let mut sorted: Vec<_> = stops.iter()
+                                      .take(MAX_GRADIENT_STOPS)
+                                      .map(|&(t, c)| (t.max(0.0).min(1.0), c))
+                                      .collect();
+        sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut gradient = Gradient {
+            stops: [(0.0, [0.0; 4]); MAX_GRADIENT_STOPS],
+            count: sorted.len(),
+        };
+        for (to, from) in gradient.stops.iter_mut().zip(sorted.into_iter()) {
+            *to = from;
+        }
+        gradient
+    }
+
+    /// Returns the stops of this gradient in order.
+    pub fn stops(&self) -> &[(Float, [f32; 4])] {
+        &self.stops[.. self.count]
+    }
+
+    /// Returns the color at position `t`.  Positions before the first stop
+    /// or after the last stop take the color of that stop.
+    pub fn color_at(&self, t: Float) -> [f32; 4] {
+        let stops = self.stops();
+        if stops.is_empty() {
+            return [0.0; 4];
+        }
+        if t <= stops[0].0 {
+            return stops[0].1;
+        }
+
+        for pair in stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+            if t <= t1 {
+                let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+                return [c0[0] + (c1[0] - c0[0]) * f,
+                        c0[1] + (c1[1] - c0[1]) * f,
+                        c0[2] + (c1[2] - c0[2]) * f,
+                        c0[3] + (c1[3] - c0[3]) * f];
+            }
+        }
+        stops[stops.len() - 1].1
+    }
+}
+
+/// How the inside of a shape is colored.
+///
+/// Gradient coordinates are in the same space as the shape's own position,
+/// so a gradient moves, rotates and scales along with the shape when it is
+/// transformed.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Fill {
+    /// A single color.
+    Solid([f32; 4]),
+    /// Colors that change along the line from `start` to `end`.
+    LinearGradient {
+        start: (Float, Float),
+        end: (Float, Float),
+        gradient: Gradient,
+    },
+    /// Colors that change with the distance from `center`, reaching the
+    /// end of the gradient at `radius`.
+    RadialGradient {
+        center: (Float, Float),
+        radius: Float,
+        gradient: Gradient,
+    },
+}
+
+impl Fill {
+    /// Creates a linear gradient from `start` to `end`.
+    pub fn linear(start: (Float, Float), end: (Float, Float), stops: &[(Float, [f32; 4])]) -> Fill {
+        Fill::LinearGradient { start: start, end: end, gradient: Gradient::new(stops) }
+    }
+
+    /// Creates a radial gradient around `center`.
+    pub fn radial(center: (Float, Float), radius: Float, stops: &[(Float, [f32; 4])]) -> Fill {
+        Fill::RadialGradient { center: center, radius: radius, gradient: Gradient::new(stops) }
+    }
+
+    /// Returns the color of this fill at the point (x, y).
+    pub fn color_at(&self, x: Float, y: Float) -> [f32; 4] {
+        match *self {
+            Fill::Solid(color) => color,
+            Fill::LinearGradient { start, end, ref gradient } => {
+                gradient.color_at(linear_position(start, end, (x, y)))
+            }
+            Fill::RadialGradient { center, radius, ref gradient } => {
+                let (dx, dy) = (x - center.0, y - center.1);
+                let t = if radius > 0.0 { (dx * dx + dy * dy).sqrt() / radius } else { 1.0 };
+                gradient.color_at(t)
+            }
+        }
+    }
+
+    /// Colors the triangles that were added to `vertices` and `idxs` after
+    /// `start`, which is their `(vertices.len(), idxs.len())` from before
+    /// the fill was built.  Everything before `start` is left alone.
+    ///
+    /// Colors are blended linearly across every triangle, so triangles are
+    /// first split wherever the gradient changes direction: at every stop of
+    /// a linear gradient, and into small pieces for a radial gradient.
+    /// `pixel_scale` is how many pixels a unit of the shape covers on
+    /// screen, which keeps radial pieces from getting smaller than a few
+    /// pixels.
+    pub fn paint(&self, start: (usize, usize), pixel_scale: Float,
+                 vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+        let (first_vertex, first_idx) = start;
+        let triangles: Vec<[Point; 3]> = idxs[first_idx ..].chunks(3).filter(|t| t.len() == 3).map(|t| {
+            let p = |i: Idx| { let v = vertices[i as usize].pos; (v[0], v[1]) };
+            [p(t[0]), p(t[1]), p(t[2])]
+        }).collect();
+
+        let pieces = match *self {
+            Fill::Solid(color) => {
+                for vertex in vertices[first_vertex ..].iter_mut() {
+                    vertex.color = color;
+                }
+                return;
+            }
+            Fill::LinearGradient { start, end, ref gradient } => {
+                let mut pieces: Vec<Vec<Point>> = triangles.iter().map(|t| t.to_vec()).collect();
+                for &(stop, _) in gradient.stops() {
+                    pieces = pieces.iter().flat_map(|piece| {
+                        split_polygon(piece, |p| linear_position(start, end, p) - stop)
+                    }).collect();
+                }
+                pieces
+            }
+            Fill::RadialGradient { radius, .. } => {
+                let max_edge = (radius / 8.0).max(MIN_RADIAL_PIECE / pixel_scale.max(1e-6));
+                let mut pieces = vec![];
+                for t in &triangles {
+                    subdivide(*t, max_edge, 5, &mut pieces);
+                }
+                pieces
+            }
+        };
+
+        vertices.truncate(first_vertex);
+        idxs.truncate(first_idx);
+        for piece in pieces {
+            let base = vertices.len() as Idx;
+            vertices.extend(piece.iter().map(|&(x, y)| {
+                ColorVertex { pos: [x, y], color: self.color_at(x, y) }
+            }));
+            for i in 1 .. (piece.len() as Idx).saturating_sub(1) {
+                idxs.extend_from_slice(&[base, base + i, base + i + 1]);
+            }
+        }
+    }
+}
+
+/// Returns how far `p` is along the line from `start` to `end`, where
+/// `start` is 0.0 and `end` is 1.0.
+fn linear_position(start: Point, end: Point, p: Point) -> Float {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq == 0.0 {
+        return 0.0;
+    }
+    ((p.0 - start.0) * dx + (p.1 - start.1) * dy) / len_sq
+}
+
+/// Splits a convex polygon into the parts where `side` is negative and
+/// where it is positive.
+fn split_polygon<F>(polygon: &[Point], side: F) -> Vec<Vec<Point>>
+where F: Fn(Point) -> Float {
+    let sides: Vec<Float> = polygon.iter().map(|&p| side(p)).collect();
+    if sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0) {
+        return vec![polygon.to_vec()];
+    }
+
+    let (mut below, mut above) = (vec![], vec![]);
+    let count = polygon.len();
+    for i in 0 .. count {
+        let j = (i + 1) % count;
+        let (a, b) = (polygon[i], polygon[j]);
+        let (sa, sb) = (sides[i], sides[j]);
+
+        if sa <= 0.0 { below.push(a); }
+        if sa >= 0.0 { above.push(a); }
+        if (sa < 0.0 && sb > 0.0) || (sa > 0.0 && sb < 0.0) {
+            let t = sa / (sa - sb);
+            let p = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+            below.push(p);
+            above.push(p);
+        }
+    }
+    vec![below, above]
+}
+
+/// Splits a triangle into four until none of its edges are longer than
+/// `max_edge`.
+fn subdivide(t: [Point; 3], max_edge: Float, depth: u32, out: &mut Vec<Vec<Point>>) {
+    let len = |a: Point, b: Point| ((a.0 - b.0) * (a.0 - b.0) + (a.1 - b.1) * (a.1 - b.1)).sqrt();
+    let longest = len(t[0], t[1]).max(len(t[1], t[2])).max(len(t[2], t[0]));
+    if depth == 0 || longest <= max_edge {
+        out.push(t.to_vec());
+        return;
+    }
+
+    let mid = |a: Point, b: Point| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let (m01, m12, m20) = (mid(t[0], t[1]), mid(t[1], t[2]), mid(t[2], t[0]));
+    subdivide([t[0], m01, m20], max_edge, depth - 1, out);
+    subdivide([m01, t[1], m12], max_edge, depth - 1, out);
+    subdivide([m20, m12, t[2]], max_edge, depth - 1, out);
+    subdivide([m01, m12, m20], max_edge, depth - 1, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fill, Gradient};
+    use super::super::gfx_integration::ColorVertex;
+    use super::super::types::Idx;
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+    const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+    fn assert_color(a: [f32; 4], b: [f32; 4]) {
+        for i in 0 .. 4 {
+            assert!((a[i] - b[i]).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    /// A square from (0, 0) to (size, size) made of two triangles.
+    fn square(size: f32, vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+        let base = vertices.len() as Idx;
+        for &(x, y) in &[(0.0, 0.0), (size, 0.0), (size, size), (0.0, size)] {
+            vertices.push(ColorVertex { pos: [x, y], color: [0.0; 4] });
+        }
+        idxs.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    #[test]
+    fn stops_are_sorted_and_clamped() {
+        let gradient = Gradient::new(&[(2.0, BLUE), (-1.0, RED)]);
+        assert_eq!(gradient.stops(), &[(0.0, RED), (1.0, BLUE)]);
+    }
+
+    #[test]
+    fn colors_are_blended_between_stops() {
+        let gradient = Gradient::new(&[(0.25, RED), (0.75, BLUE)]);
+        assert_color(gradient.color_at(0.0), RED);
+        assert_color(gradient.color_at(0.25), RED);
+        assert_color(gradient.color_at(0.5), [0.5, 0.0, 0.5, 1.0]);
+        assert_color(gradient.color_at(1.0), BLUE);
+        assert_color(Gradient::new(&[]).color_at(0.5), [0.0; 4]);
+    }
+
+    #[test]
+    fn stops_at_the_same_position_make_a_hard_edge() {
+        let gradient = Gradient::new(&[(0.0, RED), (0.5, RED), (0.5, BLUE), (1.0, BLUE)]);
+        assert_color(gradient.color_at(0.49), RED);
+        assert_color(gradient.color_at(0.51), BLUE);
+    }
+
+    #[test]
+    fn fills_follow_their_geometry() {
+        let linear = Fill::linear((0.0, 0.0), (10.0, 0.0), &[(0.0, RED), (1.0, BLUE)]);
+        assert_color(linear.color_at(5.0, 100.0), [0.5, 0.0, 0.5, 1.0]);
+        assert_color(linear.color_at(-5.0, 0.0), RED);
+
+        let radial = Fill::radial((10.0, 10.0), 10.0, &[(0.0, RED), (1.0, BLUE)]);
+        assert_color(radial.color_at(10.0, 10.0), RED);
+        assert_color(radial.color_at(10.0, 15.0), [0.5, 0.0, 0.5, 1.0]);
+        assert_color(radial.color_at(30.0, 30.0), BLUE);
+    }
+
+    #[test]
+    fn linear_paint_splits_at_stops() {
+        let (mut vertices, mut idxs) = (vec![], vec![]);
+        square(10.0, &mut vertices, &mut idxs);
+        Fill::linear((0.0, 0.0), (10.0, 0.0), &[(0.0, RED), (0.5, BLUE), (1.0, RED)])
+            .paint((0, 0), 1.0, &mut vertices, &mut idxs);
+
+        // Every vertex gets the color of the gradient where it is, and
+        // there are vertices along the middle stop.
+        for v in &vertices {
+            let expected = Fill::linear((0.0, 0.0), (10.0, 0.0), &[(0.0, RED), (0.5, BLUE), (1.0, RED)])
+                .color_at(v.pos[0], v.pos[1]);
+            assert_color(v.color, expected);
+        }
+        assert!(vertices.iter().any(|v| (v.pos[0] - 5.0).abs() < 1e-4));
+    }
+
+    #[test]
+    fn paint_leaves_earlier_geometry_alone() {
+        let (mut vertices, mut idxs) = (vec![], vec![]);
+        square(1.0, &mut vertices, &mut idxs);
+        let before = (vertices.clone(), idxs.clone());
+
+        let start = (vertices.len(), idxs.len());
+        square(10.0, &mut vertices, &mut idxs);
+        Fill::radial((5.0, 5.0), 5.0, &[(0.0, RED), (1.0, BLUE)])
+            .paint(start, 1.0, &mut vertices, &mut idxs);
+
+        assert_eq!(&idxs[.. start.1], &before.1[..]);
+        for (a, b) in vertices.iter().zip(before.0.iter()) {
+            assert_eq!(a.pos, b.pos);
+            assert_eq!(a.color, b.color);
+        }
+        assert!(idxs[start.1 ..].iter().all(|&i| i as usize >= start.0));
+        assert!(vertices.len() > start.0 + 4);
+
+        Fill::Solid(RED).paint(start, 1.0, &mut vertices, &mut idxs);
+        assert_eq!(vertices[0].color, [0.0; 4]);
+        assert!(vertices[start.0 ..].iter().all(|v| v.color == RED));
+    }
+
+    #[test]
+    fn radial_pieces_are_limited_by_screen_size() {
+        let count = |pixel_scale: f32| {
+            let (mut vertices, mut idxs) = (vec![], vec![]);
+            square(10.0, &mut vertices, &mut idxs);
+            Fill::radial((5.0, 5.0), 1.0, &[(0.0, RED), (1.0, BLUE)])
+                .paint((0, 0), pixel_scale, &mut vertices, &mut idxs);
+            idxs.len() / 3
+        };
+        // Ten units on a tiny canvas aren't worth splitting.
+        assert_eq!(count(0.1), 2);
+        assert!(count(0.1) < count(1.0));
+        assert!(count(1.0) <= count(100.0));
+        assert!(count(100.0) <= 2 * 1024);
+    }
+}
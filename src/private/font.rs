@@ -0,0 +1,538 @@
+use std::path::Path;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::collections::HashMap;
+
+use image;
+use font_atlas;
+use font_atlas::rasterize::Bitmap;
+use font_atlas::cache::FontCache;
+
+use super::accessors::StateLike;
+use super::sprite::{Sprite, Texture, TextureLoader};
+use super::error::{LuxError, LuxResult};
+use super::canvas::Text;
+use super::types::Float;
+
+/// FontLoader is implemented on any object that can load fonts into the
+/// shared font cache.
+pub trait FontLoader {
+    /// Loads a font from a path and stores it in the font cache under `name`.
+    ///
+    /// Glyphs are not rasterized until text is drawn with this font at a
+    /// particular size.
+    fn load_font<P: AsRef<Path> + ?Sized>(&self, name: &str, path: &P) -> LuxResult<()>;
+
+    /// Loads a font face that was rasterized ahead of time by `bake_font`.
+    ///
+    /// `metrics_path` is the `.bincode` file containing the glyph metrics and
+    /// `atlas_path` is the glyph atlas that they index into.  The face is
+    /// registered under `name` at `size` and no glyphs are rasterized.
+    fn load_baked_font<P, Q>(&self, name: &str, size: u32, metrics_path: &P, atlas_path: &Q) -> LuxResult<()>
+    where P: AsRef<Path> + ?Sized, Q: AsRef<Path> + ?Sized;
+
+    /// Rasterizes a loaded font at a size and writes the glyph metrics and
+    /// atlas to disk in the format read by `load_baked_font`.
+    fn bake_font<P, Q>(&self, name: &str, size: u32, metrics_path: &P, atlas_path: &Q) -> LuxResult<()>
+    where P: AsRef<Path> + ?Sized, Q: AsRef<Path> + ?Sized;
+}
+
+impl <T> FontLoader for T where T: StateLike {
+    fn load_font<P: AsRef<Path> + ?Sized>(&self, name: &str, path: &P) -> LuxResult<()> {
+        let font = try!(font_atlas::load_font(path.as_ref()));
+        self.state_fields().font_cache.borrow_mut().load_font(name, font);
+        Ok(())
+    }
+
+    fn load_baked_font<P, Q>(&self, name: &str, size: u32, metrics_path: &P, atlas_path: &Q) -> LuxResult<()>
+    where P: AsRef<Path> + ?Sized, Q: AsRef<Path> + ?Sized {
+        use bincode::SizeLimit;
+        use bincode::rustc_serialize::decode_from;
+
+        let mut reader = BufReader::new(try!(File::open(metrics_path)));
+        let serialized: SerializedFont = try!(decode_from(&mut reader, SizeLimit::Infinite));
+
+        // The atlas is flipped so that glyph rows line up with `sub_sprite`
+        // offsets the same way that rasterized faces do.
+        let img = try!(image::open(atlas_path)).flipv().to_rgba();
+        let (w, h) = img.dimensions();
+        let mut atlas = image::DynamicImage::new_rgba8(w, h);
+        {
+            use image::GenericImage;
+            for (x, y, pixel) in img.enumerate_pixels() {
+                atlas.put_pixel(x, y, image::Rgba { data: [255, 255, 255, pixel.data[3]] });
+            }
+        }
+        let sprite = try!(self.texture_from_image(atlas)).into_sprite();
+
+        let baked = BakedFont {
+            sprite: sprite,
+            line_height: serialized.line_height as Float,
+            glyphs: serialized.glyphs,
+        };
+
+        self.state_fields().baked_fonts.borrow_mut().insert((name.to_string(), size), baked);
+        Ok(())
+    }
+
+    fn bake_font<P, Q>(&self, name: &str, size: u32, metrics_path: &P, atlas_path: &Q) -> LuxResult<()>
+    where P: AsRef<Path> + ?Sized, Q: AsRef<Path> + ?Sized {
+        use bincode::SizeLimit;
+        use bincode::rustc_serialize::encode_into;
+
+        try!(ensure_face(self, name, size));
+
+        let font_cache = self.state_fields().font_cache.clone();
+        let font_cache = font_cache.borrow();
+        let commands = |text: &str| font_cache.drawing_commands(name, size as Float, text)
+                                              .map_err(|_| font_not_loaded(name));
+
+        // Advances are measured as the offset of a marker glyph that
+        // follows the character.
+        let marker_start = try!(commands("|")).first().map(|dc| dc.draw_location.0).unwrap_or(0.0);
+
+        let mut atlas = None;
+        let mut glyphs = HashMap::new();
+        for &c in font_atlas::ASCII.iter() {
+            let with_marker = try!(commands(&format!("{}|", c)));
+            let advance = with_marker.last().map(|dc| dc.draw_location.0 - marker_start)
+                                            .unwrap_or(0.0);
+
+            let alone = try!(commands(&c.to_string()));
+            let glyph = match alone.first() {
+                Some(dc) => {
+                    atlas = Some(dc.bitmap.clone());
+                    let loc = dc.bitmap_location;
+                    GlyphInfo {
+                        image_position: (loc.x, loc.y),
+                        image_size: (loc.w, loc.h),
+                        advance: (advance.round() as i32, 0),
+                        pre_draw_advance: (dc.draw_location.0.round() as i32,
+                                           -dc.draw_location.1.round() as i32),
+                    }
+                }
+                None => GlyphInfo {
+                    image_position: (0, 0),
+                    image_size: (0, 0),
+                    advance: (advance.round() as i32, 0),
+                    pre_draw_advance: (0, 0),
+                }
+            };
+            glyphs.insert(c, glyph);
+        }
+
+        let atlas = match atlas {
+            Some(atlas) => atlas,
+            None => return Err(font_not_loaded(name)),
+        };
+
+        let serialized = SerializedFont {
+            family_name: Some(name.to_string()),
+            style_name: None,
+            line_height: default_line_height(size).round() as u32,
+            max_advance: glyphs.values().map(|g| g.advance.0 as u32).max().unwrap_or(0),
+            glyphs: glyphs,
+            kerning: vec![],
+        };
+
+        let mut writer = BufWriter::new(try!(File::create(metrics_path)));
+        try!(encode_into(&serialized, &mut writer, SizeLimit::Infinite));
+
+        // Only the coverage is stored, as a grey-alpha image.
+        let pixels: Vec<Vec<(u8, u8, u8, u8)>> = atlas.texture_ref().read();
+        let height = pixels.len() as u32;
+        let width = pixels.first().map(|row| row.len()).unwrap_or(0) as u32;
+        let mut buffer = Vec::with_capacity((width * height * 2) as usize);
+        for row in &pixels {
+            for &(_, _, _, a) in row {
+                buffer.push(a);
+                buffer.push(a);
+            }
+        }
+
+        try!(image::save_buffer(atlas_path.as_ref(), &buffer[..], width, height, image::ColorType::GrayA(8)));
+        Ok(())
+    }
+}
+
+/// The on-disk representation of a baked font face.
+#[derive(RustcEncodable, RustcDecodable)]
+struct SerializedFont {
+    family_name: Option<String>,
+    style_name: Option<String>,
+    line_height: u32,
+    max_advance: u32,
+    glyphs: HashMap<char, GlyphInfo>,
+    kerning: Vec<(char, char, i32)>,
+}
+
+/// The location and metrics of a single glyph in a baked font atlas.
+#[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable)]
+struct GlyphInfo {
+    image_position: (u32, u32),
+    image_size: (u32, u32),
+    advance: (i32, i32),
+    pre_draw_advance: (i32, i32),
+}
+
+/// A font face that was rasterized ahead of time and loaded from disk.
+#[derive(Clone, Debug)]
+pub struct BakedFont {
+    sprite: Sprite,
+    line_height: Float,
+    glyphs: HashMap<char, GlyphInfo>,
+}
+
+/// Baked font faces keyed by font name and size.
+pub type BakedFonts = HashMap<(String, u32), BakedFont>;
+
+impl BakedFont {
+    /// Returns the distance between two lines of text in pixels as it was
+    /// recorded when the font was baked.
+    pub fn line_height(&self) -> Float {
+        self.line_height
+    }
+
+    /// Returns the glyph sprites of a line of text along with their
+    /// positions relative to the top left of the line.
+    fn place_glyphs(&self, line: &str) -> Vec<(Sprite, Float, Float)> {
+        let mut pen = 0.0;
+        let mut placed = vec![];
+        for c in line.chars() {
+            let glyph = match self.glyphs.get(&c) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+
+            if let Some(sprite) = self.sprite.sub_sprite(glyph.image_position, glyph.image_size) {
+                if glyph.image_size.0 != 0 && glyph.image_size.1 != 0 {
+                    // `bake_font` stores the negated draw location, so this
+                    // puts the glyph exactly where the font cache would.
+                    placed.push((sprite,
+                                 pen + glyph.pre_draw_advance.0 as Float,
+                                 -glyph.pre_draw_advance.1 as Float));
+                }
+            }
+            pen += glyph.advance.0 as Float;
+        }
+        placed
+    }
+}
+
+/// The distance between two lines of text for faces that are rasterized
+/// at runtime, and the line height that `bake_font` records.
+fn default_line_height(size: u32) -> Float {
+    size as Float * 1.25
+}
+
+/// Returns the distance between two lines of text in a face.
+fn face_line_height(baked: &BakedFonts, font: &str, size: u32) -> Float {
+    match baked.get(&(font.to_string(), size)) {
+        Some(baked) => baked.line_height,
+        None => default_line_height(size),
+    }
+}
+
+/// Uploads a rasterized glyph atlas to the GPU.
+///
+/// The glyph coverage is stored in the alpha channel so that the
+/// tint color of the textured vertices decides the color of the text.
+pub fn bitmap_to_sprite<S: StateLike>(s: &S, bitmap: Bitmap) -> LuxResult<Sprite> {
+    use image::GenericImage;
+
+    let mut img = image::DynamicImage::new_rgba8(bitmap.width() as u32, bitmap.height() as u32);
+    for (y, line) in bitmap.lines().enumerate() {
+        for (x, &pixel) in line.iter().enumerate() {
+            img.put_pixel(x as u32, (bitmap.height() - 1 - y) as u32,
+                          image::Rgba { data: [255, 255, 255, pixel] });
+        }
+    }
+
+    s.texture_from_image(img).map(Texture::into_sprite)
+}
+
+/// Makes sure that the font cache contains a face for `font` at `size`,
+/// rasterizing it if it isn't already present.
+pub fn ensure_face<S: StateLike>(s: &S, font: &str, size: u32) -> LuxResult<()> {
+    if s.state_fields().baked_fonts.borrow().contains_key(&(font.to_string(), size)) {
+        return Ok(());
+    }
+
+    let font_cache = s.state_fields().font_cache.clone();
+    let mut font_cache = font_cache.borrow_mut();
+
+    // An empty string only succeeds if the face has already been built.
+    if font_cache.drawing_commands(font, size as f32, "").is_ok() {
+        return Ok(());
+    }
+
+    font_cache.create_face(font, size as f32, font_atlas::ASCII.iter().cloned(), |bitmap| {
+        bitmap_to_sprite(s, bitmap)
+    }).map_err(|_| font_not_loaded(font))
+}
+
+/// Horizontal alignment of each line of text relative to the `x`
+/// position of the text.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HorizontalAlign {
+    /// Lines start at `x`.
+    Left,
+    /// Lines are centered on `x`.
+    Center,
+    /// Lines end at `x`.
+    Right,
+}
+
+/// Vertical anchoring of a block of text relative to the `y` position of
+/// the text.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VerticalAlign {
+    /// The first line starts at `y`.
+    Top,
+    /// The block of text is centered on `y`.
+    Middle,
+    /// The last line ends at `y`.
+    Bottom,
+}
+
+/// A single line of text that has been positioned by the layout engine.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LayoutLine {
+    /// The text contained on this line.
+    pub text: String,
+    /// The x position of the left edge of the line.
+    pub x: Float,
+    /// The y position of the top edge of the line.
+    pub y: Float,
+    /// The width of the line in pixels.
+    pub width: Float,
+}
+
+/// The result of laying out a piece of text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TextLayout {
+    /// The lines of text in order from top to bottom.
+    pub lines: Vec<LayoutLine>,
+    /// The bounding box of the text as (x, y, w, h).
+    pub bounds: (Float, Float, Float, Float),
+}
+
+/// TextMeasure is implemented on any object that can measure text without
+/// drawing it.
+///
+/// This is useful for sizing buttons and panels before any text has been
+/// rendered.
+pub trait TextMeasure {
+    /// Computes the position of every line in a `Text` object.
+    fn layout_text(&self, text: &Text) -> LuxResult<TextLayout>;
+
+    /// Returns the (width, height) that a `Text` object would take up if
+    /// it were drawn.
+    fn measure_text(&self, text: &Text) -> LuxResult<(Float, Float)> {
+        let layout = try!(self.layout_text(text));
+        Ok((layout.bounds.2, layout.bounds.3))
+    }
+}
+
+impl <T> TextMeasure for T where T: StateLike {
+    fn layout_text(&self, text: &Text) -> LuxResult<TextLayout> {
+        try!(ensure_face(self, text.font, text.size));
+        let font_cache = self.state_fields().font_cache.clone();
+        let baked_fonts = self.state_fields().baked_fonts.clone();
+        let font_cache = font_cache.borrow();
+        let baked_fonts = baked_fonts.borrow();
+        layout(&*font_cache, &*baked_fonts, text)
+    }
+}
+
+fn font_not_loaded(font: &str) -> LuxError {
+    LuxError::FontNotLoaded(format!("The font {} has not been loaded", font))
+}
+
+/// Returns the glyph sprites of a single line of text along with their
+/// positions relative to the top left of the line.
+///
+/// Baked faces take priority over faces in the font cache.
+pub fn place_glyphs(cache: &FontCache<Sprite>, baked: &BakedFonts,
+                    font: &str, size: u32, line: &str) -> LuxResult<Vec<(Sprite, Float, Float)>> {
+    if let Some(baked) = baked.get(&(font.to_string(), size)) {
+        return Ok(baked.place_glyphs(line));
+    }
+
+    let commands = try!(cache.drawing_commands(font, size as Float, line)
+                             .map_err(|_| font_not_loaded(font)));
+    Ok(commands.into_iter().filter_map(|dc| {
+        let loc = dc.bitmap_location;
+        dc.bitmap.sub_sprite((loc.x, loc.y), (loc.w, loc.h))
+                 .map(|glyph| (glyph, dc.draw_location.0, dc.draw_location.1))
+    }).collect())
+}
+
+/// Returns the width of a single line of text.
+fn line_width(cache: &FontCache<Sprite>, baked: &BakedFonts,
+              font: &str, size: u32, line: &str) -> LuxResult<Float> {
+    let glyphs = try!(place_glyphs(cache, baked, font, size, line));
+    Ok(glyphs.iter()
+             .map(|&(ref glyph, x, _)| x + glyph.width())
+             .fold(0.0, |a: Float, b| a.max(b)))
+}
+
+/// Returns the x position that a marker glyph is drawn at when it starts
+/// a line.  Advances are measured against this.
+fn marker_offset(cache: &FontCache<Sprite>, baked: &BakedFonts,
+                 font: &str, size: u32) -> LuxResult<Float> {
+    let glyphs = try!(place_glyphs(cache, baked, font, size, "|"));
+    Ok(glyphs.first().map(|&(_, x, _)| x).unwrap_or(0.0))
+}
+
+/// Returns the width of the ink of a word and the distance that the pen
+/// moves after drawing it.
+fn measure_word(cache: &FontCache<Sprite>, baked: &BakedFonts, font: &str, size: u32,
+                word: &str, marker: Float) -> LuxResult<(Float, Float)> {
+    let mut glyphs = try!(place_glyphs(cache, baked, font, size, &format!("{}|", word)));
+    let advance = match glyphs.pop() {
+        Some((_, x, _)) => x - marker,
+        None => 0.0,
+    };
+    let ink = glyphs.iter()
+                    .map(|&(ref glyph, x, _)| x + glyph.width())
+                    .fold(0.0, |a: Float, b| a.max(b));
+    Ok((ink, advance))
+}
+
+/// Breaks a paragraph into lines that are no wider than `max_width`.
+fn wrap_paragraph(cache: &FontCache<Sprite>, baked: &BakedFonts, font: &str, size: u32,
+                  paragraph: &str, max_width: Float, out: &mut Vec<String>) -> LuxResult<()> {
+    let marker = try!(marker_offset(cache, baked, font, size));
+    let (_, space) = try!(measure_word(cache, baked, font, size, " ", marker));
+    break_lines(paragraph, max_width, space, out,
+                |word| measure_word(cache, baked, font, size, word, marker))
+}
+
+/// Breaks a paragraph into lines given the advance of a space and a
+/// function that returns the ink width and advance of a word.
+///
+/// Words that are wider than `max_width` on their own are placed on a
+/// line by themselves rather than being split.  Runs of spaces are kept,
+/// except for the spaces where a line is broken.
+fn break_lines<F>(paragraph: &str, max_width: Float, space: Float, out: &mut Vec<String>,
+                  mut measure: F) -> LuxResult<()>
+where F: FnMut(&str) -> LuxResult<(Float, Float)> {
+    // Each word is only measured once, and the width of the line is kept
+    // as a running total of the advances.
+    let mut current = String::new();
+    let mut pen = 0.0;
+    for (i, word) in paragraph.split(' ').enumerate() {
+        let (ink, advance) = try!(measure(word));
+        if i == 0 {
+            current.push_str(word);
+            pen = advance;
+        } else if pen + space + ink <= max_width {
+            current.push(' ');
+            current.push_str(word);
+            pen += space + advance;
+        } else if !word.is_empty() {
+            out.push(current.trim_right_matches(' ').to_string());
+            current = word.to_string();
+            pen = advance;
+        }
+    }
+    out.push(current);
+    Ok(())
+}
+
+/// Lays out a `Text` object using faces that are already in the font cache.
+pub fn layout(cache: &FontCache<Sprite>, baked: &BakedFonts, text: &Text) -> LuxResult<TextLayout> {
+    let mut raw_lines = vec![];
+    for paragraph in text.text.split('\n') {
+        match text.max_width {
+            Some(max_width) =>
+                try!(wrap_paragraph(cache, baked, text.font, text.size, paragraph, max_width, &mut raw_lines)),
+            None => raw_lines.push(paragraph.to_string()),
+        }
+    }
+
+    let line_height = face_line_height(baked, text.font, text.size) * text.line_spacing;
+    let height = line_height * raw_lines.len() as Float;
+    let top = match text.vertical_align {
+        VerticalAlign::Top => text.y,
+        VerticalAlign::Middle => text.y - height / 2.0,
+        VerticalAlign::Bottom => text.y - height,
+    };
+
+    let mut lines = Vec::with_capacity(raw_lines.len());
+    let (mut left, mut right) = (text.x, text.x);
+    for (i, line) in raw_lines.into_iter().enumerate() {
+        let width = try!(line_width(cache, baked, text.font, text.size, &line));
+        let x = match text.align {
+            HorizontalAlign::Left => text.x,
+            HorizontalAlign::Center => text.x - width / 2.0,
+            HorizontalAlign::Right => text.x - width,
+        };
+        left = left.min(x);
+        right = right.max(x + width);
+
+        lines.push(LayoutLine {
+            text: line,
+            x: x,
+            y: top + line_height * i as Float,
+            width: width,
+        });
+    }
+
+    Ok(TextLayout {
+        lines: lines,
+        bounds: (left, top, right - left, height),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::break_lines;
+    use super::super::types::Float;
+    use super::super::error::LuxResult;
+
+    // Every character is 10 pixels wide with 1 pixel of padding on the right.
+    fn mono(word: &str) -> LuxResult<(Float, Float)> {
+        let n = word.chars().count() as Float;
+        Ok(((n * 10.0 - 1.0).max(0.0), n * 10.0))
+    }
+
+    fn wrap(text: &str, max_width: Float) -> Vec<String> {
+        let mut out = vec![];
+        break_lines(text, max_width, 10.0, &mut out, mono).unwrap();
+        out
+    }
+
+    #[test]
+    fn fits_on_one_line() {
+        assert_eq!(wrap("aa bb", 49.0), vec!["aa bb"]);
+    }
+
+    #[test]
+    fn breaks_between_words() {
+        assert_eq!(wrap("aa bb cc", 48.0), vec!["aa", "bb", "cc"]);
+        assert_eq!(wrap("aa bb cc", 78.0), vec!["aa bb", "cc"]);
+    }
+
+    #[test]
+    fn long_words_get_their_own_line() {
+        assert_eq!(wrap("a bbbbbbbb c", 30.0), vec!["a", "bbbbbbbb", "c"]);
+    }
+
+    #[test]
+    fn keeps_repeated_spaces() {
+        assert_eq!(wrap("a  b", 100.0), vec!["a  b"]);
+        assert_eq!(wrap("  a", 100.0), vec!["  a"]);
+    }
+
+    #[test]
+    fn drops_spaces_at_breaks() {
+        assert_eq!(wrap("aa   bb", 40.0), vec!["aa", "bb"]);
+    }
+
+    #[test]
+    fn empty_paragraph() {
+        assert_eq!(wrap("", 10.0), vec![""]);
+    }
+}
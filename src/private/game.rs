@@ -3,9 +3,9 @@
 use super::glutin_window::{Window, Frame};
 use super::interactive::EventIterator;
 use super::color::{rgba, rgb};
-use super::error::LuxResult;
+use super::error::{LuxError, LuxResult};
 use super::interactive::Interactive;
-use super::canvas::{Canvas, Rectangle};
+use super::canvas::{Canvas, Rectangle, Text};
 use super::raw::Transform;
 
 use std::collections::VecDeque;
@@ -17,6 +17,15 @@ use super::colors::WHITE;
 
 const FRAMES_TO_TRACK: usize = 80;
 
+/// Draws loading screen and overlay text in the default `Text` font.
+/// Nothing is drawn until the game has loaded that font.
+fn draw_debug_text(frame: &mut Frame, text: Text) {
+    match frame.draw(text) {
+        Ok(()) | Err(LuxError::FontNotLoaded(_)) => {}
+        Err(e) => panic!("Could not draw debug text: {:?}", e),
+    }
+}
+
 /// A struct that stores closures which load assets and prepare the game for
 /// running.
 pub struct Loader<G> {
@@ -136,6 +145,9 @@ pub trait Game {
     fn load(_loader: &mut Loader<Self>) where Self: Sized { }
 
     /// Draws the loading screen that shows progress when the game is loading.
+    ///
+    /// The default loading screen only shows its text after the default
+    /// `Text` font has been loaded.
     fn draw_loading_screen<'a, I>(status: I, progress: (usize, usize ), mut frame: Frame)
     where I: Iterator<Item=(&'a (usize, String), &'a LoadState)>{
         let mut buf = String::new();
@@ -148,19 +160,23 @@ pub trait Game {
         let percent = progress.0 as f32 / progress.1 as f32;
         let pos = percent * w;
 
-        /*
-        frame.text(&buf, 10.0, 0.0)
-             .color(rgb(255, 0, 0))
-             .size(30)
-             .draw()
-             .unwrap();
+        let percent_text = format!("{}%", (percent * 100.0) as u32);
 
-        frame.text(&format!("{}%", (percent * 100.0) as u32), pos - 30.0, h - 60.0)
-             .color(rgb(255, 0, 0))
-             .size(30)
-             .draw()
-             .unwrap();
-         */
+        draw_debug_text(&mut frame, Text {
+            text: &buf,
+            x: 10.0, y: 0.0,
+            size: 30,
+            color: rgb(255, 0, 0),
+            .. Default::default()
+        });
+
+        draw_debug_text(&mut frame, Text {
+            text: &percent_text,
+            x: pos - 30.0, y: h - 60.0,
+            size: 30,
+            color: rgb(255, 0, 0),
+            .. Default::default()
+        });
 
         frame.draw(Rectangle {
             x: 0.0, y:0.0,
@@ -170,21 +186,22 @@ pub trait Game {
         }).unwrap();
 
         frame.with_scissor(0, 0, pos as u32, h as u32, |frame| {
-            /*
-            frame.text(&buf, 10.0, 0.0)
-                 .color(rgb(255, 255, 255))
-                 .size(30)
-                 .draw()
-                 .unwrap();
-
-            frame.text(&format!("{}%", (percent * 100.0) as u32), pos - 30.0, h - 60.0)
-                 .color(rgb(255, 255, 255))
-                 .size(30)
-                 .draw()
-                 .unwrap();
-             */
-        });
+            draw_debug_text(frame, Text {
+                text: &buf,
+                x: 10.0, y: 0.0,
+                size: 30,
+                color: rgb(255, 255, 255),
+                .. Default::default()
+            });
 
+            draw_debug_text(frame, Text {
+                text: &percent_text,
+                x: pos - 30.0, y: h - 60.0,
+                size: 30,
+                color: rgb(255, 255, 255),
+                .. Default::default()
+            });
+        });
     }
 }
 
@@ -553,12 +570,12 @@ impl <G: Game> GameRunner<G> {
         let (fps, ups) = self.calc_fps();
         frame.with_translate(WIDTH, h, |frame| {
             frame.with_rotation(-3.1415 / 2.0, |frame| {
-                /*
-                frame.text(format!("FPS {} UPS {}", fps, ups), 0.0, 0.0)
-                     .size(12)
-                     .draw().unwrap();
-                     */
-            })
+                draw_debug_text(frame, Text {
+                    text: &format!("FPS {} UPS {}", fps, ups),
+                    size: 12,
+                    .. Default::default()
+                });
+            });
         });
     }
 }
@@ -1,5 +1,5 @@
 use glium::texture::Texture2d;
-use glium::uniforms::{self, /*SamplerWrapFunction, MinifySamplerFilter, MagnifySamplerFilter*/};
+use glium::uniforms::{self, SamplerBehavior, SamplerWrapFunction, MinifySamplerFilter, MagnifySamplerFilter};
 
 /// A colored vertex.
 #[derive(Copy, Debug, Clone)]
@@ -15,15 +15,114 @@ implement_vertex!(ColorVertex, pos, color);
 ///
 /// `tex_coords` is the position on the texture
 /// where x and y are in the range `0.0` to `1.0`.
+///
+/// `color` tints the texture.  Use white to draw the texture unchanged.
 #[derive(Copy, Debug, Clone)]
 pub struct TexVertex {
     /// The position in screen space
     pub pos: [f32; 2],
     /// The texture cooordinates [x, y] where x and y
     /// are in the range `0.0` to `1.0`.
-    pub tex_coords: [f32; 2]
+    pub tex_coords: [f32; 2],
+    /// The color in [r, g, b, a] that is multiplied against the texture.
+    pub color: [f32; 4],
+}
+implement_vertex!(TexVertex, pos, tex_coords, color);
+
+/// How texels are picked when a texture is drawn larger or smaller than
+/// its original size.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Filter {
+    /// Uses the closest texel.  Keeps pixel art sharp.
+    Nearest,
+    /// Blends the closest texels together.
+    Linear,
+}
+
+/// What happens when texture coordinates go outside of the range `0.0` to
+/// `1.0`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Wrap {
+    /// The texels on the edge of the texture are stretched outwards.
+    Clamp,
+    /// The texture is repeated.
+    Repeat,
+    /// The texture is repeated, flipping every other copy.
+    Mirror,
+}
+
+/// The settings that are used to read colors out of a texture.
+///
+/// The default sampler uses linear filtering without mipmaps and mirrored
+/// wrapping, which works with every texture, including atlas pages and
+/// textures that are drawn to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Sampler {
+    /// The filter used when the texture is drawn larger than it is.
+    pub magnify: Filter,
+    /// The filter used when the texture is drawn smaller than it is.
+    pub minify: Filter,
+    /// If true, smaller versions of the texture are used when it is drawn
+    /// smaller than it is.  Only textures that are loaded from images have
+    /// mipmaps.
+    pub mipmaps: bool,
+    /// How the texture is wrapped.
+    pub wrap: Wrap,
+}
+
+impl Default for Sampler {
+    fn default() -> Sampler {
+        Sampler {
+            magnify: Filter::Linear,
+            minify: Filter::Linear,
+            mipmaps: false,
+            wrap: Wrap::Mirror,
+        }
+    }
+}
+
+impl Sampler {
+    /// A sampler for pixel art: nearest filtering without mipmaps, and
+    /// clamped edges.
+    pub fn nearest() -> Sampler {
+        Sampler {
+            magnify: Filter::Nearest,
+            minify: Filter::Nearest,
+            mipmaps: false,
+            wrap: Wrap::Clamp,
+        }
+    }
+
+    /// A sampler with linear filtering that repeats the texture.
+    pub fn repeating() -> Sampler {
+        Sampler { wrap: Wrap::Repeat, .. Default::default() }
+    }
+
+    fn behavior(&self) -> SamplerBehavior {
+        let wrap = match self.wrap {
+            Wrap::Clamp => SamplerWrapFunction::Clamp,
+            Wrap::Repeat => SamplerWrapFunction::Repeat,
+            Wrap::Mirror => SamplerWrapFunction::Mirror,
+        };
+        let minify = match (self.minify, self.mipmaps) {
+            (Filter::Nearest, false) => MinifySamplerFilter::Nearest,
+            (Filter::Linear, false) => MinifySamplerFilter::Linear,
+            (Filter::Nearest, true) => MinifySamplerFilter::NearestMipmapNearest,
+            (Filter::Linear, true) => MinifySamplerFilter::LinearMipmapLinear,
+        };
+        let magnify = match self.magnify {
+            Filter::Nearest => MagnifySamplerFilter::Nearest,
+            Filter::Linear => MagnifySamplerFilter::Linear,
+        };
+
+        SamplerBehavior {
+            wrap_function: (wrap, wrap, wrap),
+            minify_filter: minify,
+            magnify_filter: magnify,
+            .. Default::default()
+        }
+    }
 }
-implement_vertex!(TexVertex, pos, tex_coords);
 
 pub struct ColorParams {
     pub matrix: [[f32; 4]; 4],
@@ -39,18 +138,13 @@ impl uniforms::Uniforms for ColorParams {
 pub struct TexParams<'a> {
     pub matrix: [[f32; 4]; 4],
     pub tex: &'a Texture2d,
-/*  pub wrap_function: SamplerWrapFunction,
-    pub minify_filter: MinifySamplerFilter,
-    pub magnify_filter: MagnifySamplerFilter,
-*/
-    pub color_mult: [f32; 4]
+    pub sampler: Sampler,
 }
 
 impl <'a> uniforms::Uniforms for TexParams<'a> {
     fn visit_values<'b, F>(&'b self, mut f: F) where F: FnMut(&str, uniforms::UniformValue<'b>) {
         use glium::uniforms::AsUniformValue;
         f("matrix", self.matrix.as_uniform_value());
-        f("tex", self.tex.as_uniform_value());
-        f("color_mult", self.color_mult.as_uniform_value());
+        f("tex", uniforms::UniformValue::Texture2d(self.tex, Some(self.sampler.behavior())));
     }
 }
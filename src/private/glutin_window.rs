@@ -12,12 +12,13 @@ use super::interactive::keycodes::VirtualKeyCode;
 
 use super::accessors::{Fetch, StateLike, StateFields, DrawLike, DrawFields, DrawFieldsRef};
 use super::interactive::{EventIterator, AbstractKey, Event, Interactive};
-use super::gfx_integration::{ColorVertex, TexVertex};
+use super::gfx_integration::{ColorVertex, TexVertex, Sampler};
 use super::canvas::Canvas;
 use super::sprite::Sprite;
+use super::font::BakedFonts;
 use super::color::Color;
 use super::raw::Transform;
-use super::error::LuxResult;
+use super::error::{LuxError, LuxResult};
 use super::shaders::{gen_texture_shader, gen_color_shader};
 use super::primitive_canvas::{
     PrimitiveCanvas,
@@ -45,7 +46,83 @@ pub struct WindowOptions {
     /// True if the window should be transparent.
     pub transparent: bool,
     /// True if the window should have no border or title-bar.
-    pub decorations: bool
+    pub decorations: bool,
+    /// Draws every frame at a fixed logical size and scales it to fit the
+    /// window.  `None` maps one unit to one pixel of the window.
+    pub virtual_resolution: Option<VirtualResolution>,
+}
+
+/// How a virtual resolution is scaled up to the size of the window.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScaleMode {
+    /// Scales as much as possible while keeping the aspect ratio, leaving
+    /// bars on two sides of the window.
+    Letterbox,
+    /// Fills the whole window, stretching the image if the aspect ratios
+    /// don't match.
+    Stretch,
+    /// Only scales by whole numbers so that every logical pixel is the
+    /// same size, leaving bars around the image.  Good for pixel art.
+    Integer,
+}
+
+/// A fixed logical size that frames are drawn at, no matter how big the
+/// window is.
+///
+/// Frames of a window with a virtual resolution are drawn into an
+/// offscreen image at the logical size, which is scaled onto the window
+/// with nearest filtering when the frame is done.  Frames report the
+/// logical size from `Canvas::size`, and the mouse position of the window
+/// is given in logical coordinates.  The bars around the scaled image are
+/// always black.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::window::{WindowOptions, VirtualResolution, ScaleMode};
+///# fn main() {
+/// let mut window = Window::new(WindowOptions {
+///     dimensions: (1280, 720),
+///     virtual_resolution: Some(VirtualResolution {
+///         size: (320, 180),
+///         mode: ScaleMode::Integer,
+///     }),
+///     .. Default::default()
+/// }).unwrap();
+///# }
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct VirtualResolution {
+    /// The logical size in pixels.
+    pub size: (u32, u32),
+    pub mode: ScaleMode,
+}
+
+/// The offscreen image that frames of a window with a virtual resolution
+/// are drawn into.
+#[derive(Clone)]
+struct Offscreen {
+    resolution: VirtualResolution,
+    display: glium::Display,
+    color: Rc<glium::texture::Texture2d>,
+    depth_stencil: Rc<glium::framebuffer::DepthStencilRenderBuffer>,
+}
+
+/// The surface that a frame draws on.  This is the window itself, or the
+/// offscreen image when the window has a virtual resolution.
+pub struct FrameSurface {
+    frame: glium::Frame,
+    offscreen: Option<Offscreen>,
+}
+
+/// Where the logical image ends up on a surface.
+#[derive(Copy, Clone, Debug)]
+struct Viewport {
+    logical: (Float, Float),
+    /// The position of the logical origin on the surface in pixels.
+    offset: (Float, Float),
+    /// The size of a logical pixel on the surface.
+    scale: (Float, Float),
 }
 
 /// A 1 to 1 correlation with a window shown on your desktop.
@@ -58,6 +135,7 @@ pub struct Window {
     display: glium::Display,
     color_program: Rc<glium::Program>,
     tex_program: Rc<glium::Program>,
+    offscreen: Option<Offscreen>,
     closed: bool,
 
     // WINDOW
@@ -68,6 +146,7 @@ pub struct Window {
     tex_vtx_cache: poison_pool::PoisonPool<Vec<TexVertex>>,
     color_vtx_cache: poison_pool::PoisonPool<Vec<ColorVertex>>,
     font_cache: Rc<RefCell<FontCache<Sprite>>>,
+    baked_fonts: Rc<RefCell<BakedFonts>>,
 
     // EVENT
     event_store: VecDeque<Event>,
@@ -91,7 +170,7 @@ pub struct Window {
 /// implies - use one Frame instance per frame.
 pub struct Frame {
     display: glium::Display,
-    f: glium::Frame,
+    f: FrameSurface,
     color_program: Rc<glium::Program>,
     tex_program: Rc<glium::Program>,
 
@@ -104,9 +183,11 @@ pub struct Frame {
     tex_vtx_cache: poison_pool::PoisonPool<Vec<TexVertex>>,
     color_vtx_cache: poison_pool::PoisonPool<Vec<ColorVertex>>,
     font_cache: Rc<RefCell<FontCache<Sprite>>>,
+    baked_fonts: Rc<RefCell<BakedFonts>>,
 
     // Raw
     basis_matrix: Mat4f,
+    base_matrix: Mat4f,
 
     // Misc
     draw_mod: DrawParamModifier,
@@ -121,25 +202,24 @@ impl Frame {
            tex_vtx_cache: poison_pool::PoisonPool<Vec<TexVertex>>,
            color_vtx_cache: poison_pool::PoisonPool<Vec<ColorVertex>>,
            font_cache: Rc<RefCell<FontCache<Sprite>>>,
-           clear_color: Option<[f32; 4]>) -> Frame {
+           baked_fonts: Rc<RefCell<BakedFonts>>,
+           clear_color: Option<[f32; 4]>,
+           offscreen: Option<Offscreen>) -> Frame {
         use glium::Surface;
 
         let mut frm = display.draw();
-        if let Some(c) = clear_color {
-            frm.clear_color(c[0],c[1],c[2],c[3]);
+        if offscreen.is_some() {
+            // Clear the bars, which are never drawn to.
+            frm.clear_color(0.0, 0.0, 0.0, 1.0);
         }
-        frm.clear_stencil(0);
 
-        let size = frm.get_dimensions();
-        let (w, h) = (size.0 as f32, size.1 as f32);
-        let (sx, sy) = (2.0 / w, -2.0 / h);
+        let mut surface = FrameSurface { frame: frm, offscreen: offscreen };
+        if let Some(c) = clear_color {
+            surface.clear_color(c[0],c[1],c[2],c[3]);
+        }
+        surface.clear_stencil(0);
 
-        let mut basis = vecmath::mat4_id();
-        basis[1][1] = -1.0;
-        basis[3][0] = -1.0;
-        basis[3][1] = 1.0;
-        basis[0][0] = sx;
-        basis[1][1] = sy;
+        let basis = pixel_basis(surface.get_dimensions());
 
         Frame {
             display: display.clone(),
@@ -149,19 +229,219 @@ impl Frame {
             tex_vtx_cache: tex_vtx_cache,
             color_vtx_cache: color_vtx_cache,
             font_cache: font_cache,
-            f: frm,
+            baked_fonts: baked_fonts,
+            f: surface,
             color_draw_cache: None,
             tex_draw_cache: None,
             basis_matrix: basis,
+            base_matrix: basis,
             draw_mod: DrawParamModifier::new()
         }
     }
+
+    /// Scales the offscreen image of a window with a virtual resolution
+    /// onto the window.
+    fn present(&mut self) -> LuxResult<()> {
+        use glium::Surface;
+        use glium::index::PrimitiveType::TrianglesList;
+
+        // Everything drawn after this goes straight to the window.
+        let offscreen = match self.f.offscreen.take() {
+            Some(offscreen) => offscreen,
+            None => return Ok(()),
+        };
+        // Scissors and stencils only apply to the logical image.
+        self.draw_mod = DrawParamModifier::new();
+
+        let window = self.f.get_dimensions();
+        let (x, y, w, h) = offscreen.resolution.view_rect(window);
+        let white = [1.0, 1.0, 1.0, 1.0];
+        // Rows of the offscreen image go from the bottom up.
+        let vertices = [
+            TexVertex { pos: [x, y], tex_coords: [0.0, 1.0], color: white },
+            TexVertex { pos: [x + w, y], tex_coords: [1.0, 1.0], color: white },
+            TexVertex { pos: [x + w, y + h], tex_coords: [1.0, 0.0], color: white },
+            TexVertex { pos: [x, y + h], tex_coords: [0.0, 0.0], color: white },
+        ];
+        let idxs = [0, 1, 2, 0, 2, 3];
+
+        self.draw_textured_now(TrianglesList, &vertices[..], Some(&idxs[..]),
+                               Some(pixel_basis(window)), &offscreen.color, Sampler::nearest())
+    }
 }
 
 impl Drop for Frame {
     fn drop(&mut self) {
         self.flush_draw().unwrap();
-        self.f.set_finish().unwrap();
+        self.present().unwrap();
+        self.f.frame.set_finish().unwrap();
+    }
+}
+
+/// Returns the matrix that turns pixels on a `size` surface, counted from
+/// the top left, into the coordinates that the graphics card expects.
+fn pixel_basis(size: (u32, u32)) -> Mat4f {
+    let (w, h) = (size.0 as f32, size.1 as f32);
+    let mut basis = vecmath::mat4_id();
+    basis[0][0] = 2.0 / w;
+    basis[1][1] = -2.0 / h;
+    basis[3][0] = -1.0;
+    basis[3][1] = 1.0;
+    basis
+}
+
+impl Offscreen {
+    fn new(display: &glium::Display, resolution: VirtualResolution) -> LuxResult<Offscreen> {
+        use glium::framebuffer::{DepthStencilRenderBuffer, SimpleFrameBuffer};
+        use glium::texture::DepthStencilFormat;
+
+        let (w, h) = resolution.size;
+        let color = try!(glium::texture::Texture2d::empty(display, w, h));
+        let depth_stencil = try!(DepthStencilRenderBuffer::new(display, DepthStencilFormat::I24I8, w, h)
+            .map_err(|e| LuxError::OpenGlError(format!("{:?}", e))));
+        // Check that the two work together now, so that drawing to them
+        // later can't fail.
+        try!(SimpleFrameBuffer::with_depth_stencil_buffer(display, &color, &depth_stencil)
+            .map_err(|e| LuxError::OpenGlError(format!("{:?}", e))));
+
+        Ok(Offscreen {
+            resolution: resolution,
+            display: display.clone(),
+            color: Rc::new(color),
+            depth_stencil: Rc::new(depth_stencil),
+        })
+    }
+
+    fn surface(&self) -> glium::framebuffer::SimpleFrameBuffer {
+        glium::framebuffer::SimpleFrameBuffer::with_depth_stencil_buffer(
+            &self.display, &*self.color, &*self.depth_stencil).unwrap()
+    }
+}
+
+impl glium::Surface for FrameSurface {
+    fn clear(&mut self, rect: Option<&glium::Rect>, color: Option<(f32, f32, f32, f32)>,
+             color_srgb: bool, depth: Option<f32>, stencil: Option<i32>) {
+        match self.offscreen {
+            Some(ref o) => o.surface().clear(rect, color, color_srgb, depth, stencil),
+            None => self.frame.clear(rect, color, color_srgb, depth, stencil),
+        }
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        match self.offscreen {
+            Some(ref o) => o.resolution.size,
+            None => self.frame.get_dimensions(),
+        }
+    }
+
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        match self.offscreen {
+            Some(ref o) => o.surface().get_depth_buffer_bits(),
+            None => self.frame.get_depth_buffer_bits(),
+        }
+    }
+
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        match self.offscreen {
+            Some(ref o) => o.surface().get_stencil_buffer_bits(),
+            None => self.frame.get_stencil_buffer_bits(),
+        }
+    }
+
+    fn draw<'a, 'b, V, I, U>(&mut self, vertices: V, indices: I, program: &glium::Program,
+                             uniforms: &U, draw_parameters: &glium::DrawParameters)
+    -> Result<(), glium::DrawError>
+    where V: glium::vertex::MultiVerticesSource<'b>,
+          I: Into<glium::index::IndicesSource<'a>>,
+          U: glium::uniforms::Uniforms {
+        match self.offscreen {
+            Some(ref o) => o.surface().draw(vertices, indices, program, uniforms, draw_parameters),
+            None => self.frame.draw(vertices, indices, program, uniforms, draw_parameters),
+        }
+    }
+
+    fn blit_from_frame(&self, source_rect: &glium::Rect, target_rect: &glium::BlitTarget,
+                       filter: glium::uniforms::MagnifySamplerFilter) {
+        match self.offscreen {
+            Some(ref o) => o.surface().blit_from_frame(source_rect, target_rect, filter),
+            None => self.frame.blit_from_frame(source_rect, target_rect, filter),
+        }
+    }
+
+    fn blit_from_simple_framebuffer(&self, source: &glium::framebuffer::SimpleFrameBuffer,
+                                    source_rect: &glium::Rect, target_rect: &glium::BlitTarget,
+                                    filter: glium::uniforms::MagnifySamplerFilter) {
+        match self.offscreen {
+            Some(ref o) => o.surface().blit_from_simple_framebuffer(source, source_rect,
+                                                                    target_rect, filter),
+            None => self.frame.blit_from_simple_framebuffer(source, source_rect,
+                                                            target_rect, filter),
+        }
+    }
+
+    fn blit_from_multioutput_framebuffer(&self, source: &glium::framebuffer::MultiOutputFrameBuffer,
+                                         source_rect: &glium::Rect,
+                                         target_rect: &glium::BlitTarget,
+                                         filter: glium::uniforms::MagnifySamplerFilter) {
+        match self.offscreen {
+            Some(ref o) => o.surface().blit_from_multioutput_framebuffer(source, source_rect,
+                                                                         target_rect, filter),
+            None => self.frame.blit_from_multioutput_framebuffer(source, source_rect,
+                                                                 target_rect, filter),
+        }
+    }
+
+    fn blit_color<S>(&self, source_rect: &glium::Rect, target: &S,
+                     target_rect: &glium::BlitTarget,
+                     filter: glium::uniforms::MagnifySamplerFilter) where S: glium::Surface {
+        match self.offscreen {
+            Some(ref o) => o.surface().blit_color(source_rect, target, target_rect, filter),
+            None => self.frame.blit_color(source_rect, target, target_rect, filter),
+        }
+    }
+}
+
+impl VirtualResolution {
+    fn viewport(&self, surface: (u32, u32)) -> Viewport {
+        let (sw, sh) = (surface.0 as Float, surface.1 as Float);
+        let (lw, lh) = (self.size.0 as Float, self.size.1 as Float);
+        let fit = (sw / lw).min(sh / lh);
+        let scale = match self.mode {
+            ScaleMode::Stretch => (sw / lw, sh / lh),
+            ScaleMode::Letterbox => (fit, fit),
+            // Windows that are too small still get a scale of one, which
+            // crops the edges off.
+            ScaleMode::Integer => {
+                let s = fit.floor().max(1.0);
+                (s, s)
+            }
+        };
+        Viewport {
+            logical: (lw, lh),
+            offset: (((sw - lw * scale.0) / 2.0).floor(), ((sh - lh * scale.1) / 2.0).floor()),
+            scale: scale,
+        }
+    }
+
+    /// Returns the part of a `surface_size` window that the logical image
+    /// is drawn into, as (x, y, w, h) in window pixels.
+    pub fn view_rect(&self, surface_size: (u32, u32)) -> (Float, Float, Float, Float) {
+        let v = self.viewport(surface_size);
+        (v.offset.0, v.offset.1, v.logical.0 * v.scale.0, v.logical.1 * v.scale.1)
+    }
+
+    /// Turns a position in a `surface_size` window into logical
+    /// coordinates.
+    pub fn to_logical(&self, surface_size: (u32, u32), (x, y): (Float, Float)) -> (Float, Float) {
+        let v = self.viewport(surface_size);
+        ((x - v.offset.0) / v.scale.0, (y - v.offset.1) / v.scale.1)
+    }
+
+    /// Turns logical coordinates into a position in a `surface_size`
+    /// window.
+    pub fn to_surface(&self, surface_size: (u32, u32), (x, y): (Float, Float)) -> (Float, Float) {
+        let v = self.viewport(surface_size);
+        (v.offset.0 + x * v.scale.0, v.offset.1 + y * v.scale.1)
     }
 }
 
@@ -174,7 +454,8 @@ impl WindowOptions {
             vsync,
             multisampling,
             transparent,
-            decorations
+            decorations,
+            virtual_resolution: _,
         } = self;
 
         let builder = glium::glutin::WindowBuilder::new()
@@ -197,7 +478,8 @@ impl Default for WindowOptions {
             multisampling: 1,
             vsync: true,
             transparent: false,
-            decorations: true
+            decorations: true,
+            virtual_resolution: None,
         }
     }
 }
@@ -216,18 +498,26 @@ impl Window {
         let (width, height): (u32, u32) = display.get_framebuffer_dimensions();
 
         let font_cache = Rc::new(RefCell::new(FontCache::new()));
+        let baked_fonts = Rc::new(RefCell::new(HashMap::new()));
+
+        let offscreen = match options.virtual_resolution {
+            Some(v) => Some(try!(Offscreen::new(&display, v))),
+            None => None,
+        };
 
         let window = Window {
             options: options,
             display: display,
             color_program: Rc::new(color_program),
             tex_program: Rc::new(tex_program),
+            offscreen: offscreen,
             closed: false,
             title: "Lux".to_string(),
             idx_cache: poison_pool::PoisonPool::new(4, || vec![]),
             tex_vtx_cache: poison_pool::PoisonPool::new(4, || vec![]),
             color_vtx_cache: poison_pool::PoisonPool::new(4, || vec![]),
             font_cache: font_cache,
+            baked_fonts: baked_fonts,
             event_store: VecDeque::new(),
             mouse_pos: (0, 0),
             window_pos: (0, 0),
@@ -255,10 +545,41 @@ impl Window {
     pub fn change_options<F: FnOnce(&mut WindowOptions)>(&mut self, f: F) -> LuxResult<()> {
         use glium::DisplayBuild;
         let copy = self.options.clone();
+        let old_resolution = copy.virtual_resolution;
         f(&mut self.options);
-        if copy != self.options {
+        // The virtual resolution is only used when frames are made, so
+        // changing it doesn't need a new window.
+        let window_changed = WindowOptions {
+            virtual_resolution: self.options.virtual_resolution,
+            .. copy
+        } != self.options;
+        if window_changed {
             try!(self.options.clone().into_window_builder().rebuild_glium(&self.display));
         }
+        if self.options.virtual_resolution != old_resolution {
+            try!(self.update_offscreen());
+        }
+        Ok(())
+    }
+
+    /// Makes the offscreen image match the virtual resolution.
+    fn update_offscreen(&mut self) -> LuxResult<()> {
+        let resolution = match self.options.virtual_resolution {
+            Some(resolution) => resolution,
+            None => {
+                self.offscreen = None;
+                return Ok(());
+            }
+        };
+        // Switching the scale mode keeps the image that is already there.
+        let same_size = self.offscreen.as_ref().map(|o| o.resolution.size) == Some(resolution.size);
+        if same_size {
+            if let Some(ref mut offscreen) = self.offscreen {
+                offscreen.resolution = resolution;
+            }
+        } else {
+            self.offscreen = Some(try!(Offscreen::new(&self.display, resolution)));
+        }
         Ok(())
     }
 
@@ -294,7 +615,8 @@ impl Window {
             match event {
             glevent::MouseMoved(x, y) => {
                 self.mouse_pos = (x as i32, y as i32);
-                self.event_store.push_back(MouseMoved((x as i32, y as i32)))
+                let pos = self.mouse_pos_i();
+                self.event_store.push_back(MouseMoved(pos))
             }
             glevent::MouseInput(glutin::ElementState::Pressed, button) => {
                 self.event_store.push_back(MouseDown(t_mouse(button)));
@@ -386,6 +708,21 @@ impl Window {
         }}
     }
 
+    /// Returns the size that frames are drawn at.  This is the virtual
+    /// resolution if there is one, or the size of the window otherwise.
+    pub fn logical_size(&self) -> (Float, Float) {
+        match self.options.virtual_resolution {
+            Some(v) => (v.size.0 as Float, v.size.1 as Float),
+            None => self.get_size(),
+        }
+    }
+
+    /// Returns the position of the mouse in window pixels, ignoring the
+    /// virtual resolution.
+    pub fn raw_mouse_pos(&self) -> (Float, Float) {
+        (self.mouse_pos.0 as Float, self.mouse_pos.1 as Float)
+    }
+
     /// Produce a frame that has been cleared with a color.
     pub fn cleared_frame<C: Color>(&mut self, clear_color: C) -> Frame {
         Frame::new(&self.display,
@@ -395,7 +732,9 @@ impl Window {
                    self.tex_vtx_cache.clone(),
                    self.color_vtx_cache.clone(),
                    self.font_cache.clone(),
-                   Some(clear_color.to_rgba()))
+                   self.baked_fonts.clone(),
+                   Some(clear_color.to_rgba()),
+                   self.offscreen.clone())
     }
 
     /// Produce a frame that has not been cleared.
@@ -407,7 +746,9 @@ impl Window {
                    self.tex_vtx_cache.clone(),
                    self.color_vtx_cache.clone(),
                    self.font_cache.clone(),
-                   None)
+                   self.baked_fonts.clone(),
+                   None,
+                   self.offscreen.clone())
     }
 }
 
@@ -416,6 +757,7 @@ impl StateLike for Frame {
         StateFields {
             display: &self.display,
             font_cache: &self.font_cache,
+            baked_fonts: &self.baked_fonts,
             texture_shader: &self.tex_program,
             color_shader: &self.color_program,
         }
@@ -427,6 +769,7 @@ impl StateLike for Window {
         StateFields {
             display: &self.display,
             font_cache: &self.font_cache,
+            baked_fonts: &self.baked_fonts,
             texture_shader: &self.tex_program,
             color_shader: &self.color_program,
         }
@@ -434,13 +777,15 @@ impl StateLike for Window {
 }
 
 impl DrawLike for Frame {
-    type Surface = glium::Frame;
+    type Surface = FrameSurface;
     fn draw_fields(&mut self) -> DrawFields<Self::Surface> {
         DrawFields {
             display: &mut self.display,
             scissor: &mut self.draw_mod.scissor,
             stencil_state: &mut self.draw_mod.stencil_state,
+            state_stack: &mut self.draw_mod.state_stack,
             font_cache: &mut self.font_cache,
+            baked_fonts: &mut self.baked_fonts,
             texture_shader: &mut self.tex_program,
             color_shader: &mut self.color_program,
             color_draw_cache: &mut self.color_draw_cache,
@@ -455,7 +800,9 @@ impl DrawLike for Frame {
             display: &self.display,
             scissor: &self.draw_mod.scissor,
             stencil_state: &self.draw_mod.stencil_state,
+            state_stack: &self.draw_mod.state_stack,
             font_cache: &self.font_cache,
+            baked_fonts: &self.baked_fonts,
             texture_shader: &self.tex_program,
             color_shader: &self.color_program,
             color_draw_cache: &self.color_draw_cache,
@@ -512,11 +859,21 @@ impl Interactive for Window {
     }
 
     fn mouse_pos_i(&self) -> (i32, i32) {
-        self.mouse_pos
+        match self.options.virtual_resolution {
+            Some(_) => {
+                let (x, y) = self.mouse_pos();
+                (x.floor() as i32, y.floor() as i32)
+            }
+            None => self.mouse_pos,
+        }
     }
 
     fn mouse_pos(&self) -> (f32, f32) {
-        (self.mouse_pos.0 as f32, self.mouse_pos.1 as f32)
+        let pos = self.raw_mouse_pos();
+        match self.options.virtual_resolution {
+            Some(v) => v.to_logical(self.window_size, pos),
+            None => pos,
+        }
     }
 
     fn is_mouse_down(&self) -> bool {
@@ -547,6 +904,10 @@ impl Transform for Frame {
     fn current_matrix(&self) -> &[[f32; 4]; 4] {
         &self.basis_matrix
     }
+
+    fn base_matrix(&self) -> [[f32; 4]; 4] {
+        self.base_matrix
+    }
 }
 
 
@@ -573,3 +934,43 @@ impl Fetch<Vec<ColorVertex>> for Frame {
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{VirtualResolution, ScaleMode};
+
+    fn resolution(mode: ScaleMode) -> VirtualResolution {
+        VirtualResolution { size: (320, 180), mode: mode }
+    }
+
+    #[test]
+    fn integer_mode_scales_by_whole_logical_pixels() {
+        let v = resolution(ScaleMode::Integer);
+        assert_eq!(v.view_rect((1000, 600)), (20.0, 30.0, 960.0, 540.0));
+        assert_eq!(v.view_rect((1279, 719)), (159.0, 89.0, 960.0, 540.0));
+        // Windows that are too small crop the edges off.
+        assert_eq!(v.view_rect((300, 100)), (-10.0, -40.0, 320.0, 180.0));
+    }
+
+    #[test]
+    fn letterbox_and_stretch_fill_the_window() {
+        assert_eq!(resolution(ScaleMode::Letterbox).view_rect((640, 480)),
+                   (0.0, 60.0, 640.0, 360.0));
+        assert_eq!(resolution(ScaleMode::Stretch).view_rect((640, 480)),
+                   (0.0, 0.0, 640.0, 480.0));
+    }
+
+    #[test]
+    fn mouse_positions_round_trip() {
+        for &mode in &[ScaleMode::Integer, ScaleMode::Letterbox, ScaleMode::Stretch] {
+            let v = resolution(mode);
+            let window = (1000, 600);
+            assert_eq!(v.to_logical(window, v.to_surface(window, (0.0, 0.0))), (0.0, 0.0));
+            let (x, y) = v.to_logical(window, v.to_surface(window, (160.5, 90.25)));
+            assert!((x - 160.5).abs() < 1e-3 && (y - 90.25).abs() < 1e-3, "{:?}", (x, y));
+        }
+        let v = resolution(ScaleMode::Integer);
+        assert_eq!(v.to_logical((1000, 600), (20.0, 30.0)), (0.0, 0.0));
+        assert_eq!(v.to_logical((1000, 600), (23.0, 36.0)), (1.0, 2.0));
+    }
+}
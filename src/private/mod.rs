@@ -1,6 +1,7 @@
 pub mod interactive;
 pub mod canvas;
 pub mod raw;
+pub mod affine;
 pub mod gfx_integration;
 pub mod glutin_window;
 pub mod color;
@@ -12,3 +13,14 @@ pub mod accessors;
 pub mod primitive_canvas;
 pub mod shaders;
 pub mod types;
+pub mod font;
+pub mod tessellate;
+pub mod fill;
+pub mod path;
+pub mod atlas;
+pub mod sheet_json;
+pub mod animation;
+pub mod xml;
+pub mod tilemap;
+pub mod particles;
+pub mod camera;
@@ -0,0 +1,504 @@
+use std::cmp;
+use std::f32::consts::PI;
+
+use glium::index::PrimitiveType::TrianglesList;
+
+use super::canvas::{Canvas, Drawable};
+use super::error::LuxResult;
+use super::gfx_integration::{ColorVertex, TexVertex};
+use super::sprite::Sprite;
+use super::types::{Float, Idx};
+
+/// What each particle looks like.
+#[derive(Clone, Debug)]
+pub enum ParticleShape {
+    /// A square that is `size` pixels wide.
+    Square,
+    /// A circle that is `size` pixels across, drawn with this many
+    /// segments.
+    Circle(u32),
+    /// A sprite that is stretched to `size` pixels across and tinted by the
+    /// particle color.
+    Sprite(Sprite),
+}
+
+/// The settings of a `ParticleEmitter`.
+///
+/// Settings that are given as a `(min, max)` pair are picked at random
+/// between the two for every particle.
+#[derive(Clone, Debug)]
+pub struct EmitterOptions {
+    /// How many particles are spawned per second while the emitter is
+    /// emitting.
+    pub rate: Float,
+    /// The most particles that can be alive at once.  No new particles are
+    /// spawned while the emitter is full.
+    pub max_particles: usize,
+    /// How long each particle lives in seconds.
+    pub lifetime: (Float, Float),
+    /// How fast particles move when they are spawned in pixels per second.
+    pub speed: (Float, Float),
+    /// The direction that particles move in when they are spawned, in
+    /// radians.  `0.0` is to the right and `PI / 2.0` is down.
+    pub direction: (Float, Float),
+    /// The acceleration applied to every particle in pixels per second
+    /// squared.
+    pub gravity: (Float, Float),
+    /// How much of its velocity a particle loses per second, from `0.0`
+    /// to `1.0`.
+    pub drag: Float,
+    /// The width and height of the area centered on the emitter that
+    /// particles are spawned in.
+    pub spawn_area: (Float, Float),
+    /// The color of a particle over its life.  The colors are spread evenly
+    /// from birth to death and blended between.
+    pub colors: Vec<[f32; 4]>,
+    /// The size of a particle over its life in pixels, spread out the same
+    /// way as `colors`.
+    pub sizes: Vec<Float>,
+    /// The angle that particles start out at in radians.
+    pub rotation: (Float, Float),
+    /// How fast particles spin in radians per second.
+    pub spin: (Float, Float),
+    pub shape: ParticleShape,
+}
+
+impl Default for EmitterOptions {
+    fn default() -> EmitterOptions {
+        EmitterOptions {
+            rate: 50.0,
+            max_particles: 1000,
+            lifetime: (1.0, 1.0),
+            speed: (50.0, 100.0),
+            direction: (0.0, 2.0 * PI),
+            gravity: (0.0, 0.0),
+            drag: 0.0,
+            spawn_area: (0.0, 0.0),
+            colors: vec![[1.0, 1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 0.0]],
+            sizes: vec![5.0],
+            rotation: (0.0, 0.0),
+            spin: (0.0, 0.0),
+            shape: ParticleShape::Square,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Particle {
+    pos: (Float, Float),
+    vel: (Float, Float),
+    age: Float,
+    lifetime: Float,
+    rotation: Float,
+    spin: Float,
+}
+
+/// Spawns, moves and draws particles.
+///
+/// Call `update` from `Game::update` and draw the emitter from
+/// `Game::render`.  Every particle of an emitter is drawn with a single
+/// draw call.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::{ParticleEmitter, EmitterOptions};
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let mut sparks = ParticleEmitter::new(EmitterOptions {
+///     rate: 200.0,
+///     gravity: (0.0, 300.0),
+///     colors: vec![rgb(1.0, 1.0, 0.0), rgba(1.0, 0.0, 0.0, 0.0)],
+///     .. Default::default()
+/// });
+///
+/// while window.is_open() {
+///     let mut frame = window.cleared_frame(rgb(0.0, 0.0, 0.0));
+///     sparks.set_position(window.mouse_x(), window.mouse_y());
+///     sparks.update(1.0 / 60.0);
+///     frame.draw(&sparks).unwrap();
+/// }
+///# }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParticleEmitter {
+    pub options: EmitterOptions,
+    /// Where new particles are spawned.
+    pub x: Float,
+    pub y: Float,
+    /// Particles are only spawned over time while this is true.  Bursts
+    /// are always spawned.
+    pub emitting: bool,
+    particles: Vec<Particle>,
+    /// Fractions of a particle that are carried over between updates.
+    to_spawn: Float,
+    rng: XorShift,
+}
+
+impl ParticleEmitter {
+    /// Creates an emitter at (0, 0) that starts emitting right away.
+    pub fn new(options: EmitterOptions) -> ParticleEmitter {
+        ParticleEmitter {
+            options: options,
+            x: 0.0,
+            y: 0.0,
+            emitting: true,
+            particles: vec![],
+            to_spawn: 0.0,
+            rng: XorShift::new(0x9E3779B9),
+        }
+    }
+
+    /// Seeds the random numbers that particles are spawned with, so the
+    /// same updates always produce the same particles.
+    pub fn set_seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+
+    /// Moves the point that new particles are spawned at.  Particles that
+    /// are already alive are not moved.
+    pub fn set_position(&mut self, x: Float, y: Float) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Returns the number of particles that are alive.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Returns true if no particles are alive.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Removes every particle.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.to_spawn = 0.0;
+    }
+
+    /// Spawns `count` particles at once, up to `max_particles`.
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0 .. count {
+            if self.particles.len() >= self.options.max_particles {
+                break;
+            }
+            let particle = self.spawn();
+            self.particles.push(particle);
+        }
+    }
+
+    /// Moves every particle forward by `dt` seconds, removes the ones that
+    /// have died and spawns new ones.
+    pub fn update(&mut self, dt: Float) {
+        let (gx, gy) = self.options.gravity;
+        let drag = (1.0 - self.options.drag * dt).max(0.0);
+        for p in &mut self.particles {
+            p.age += dt;
+            p.vel.0 = (p.vel.0 + gx * dt) * drag;
+            p.vel.1 = (p.vel.1 + gy * dt) * drag;
+            p.pos.0 += p.vel.0 * dt;
+            p.pos.1 += p.vel.1 * dt;
+            p.rotation += p.spin * dt;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        if self.emitting {
+            self.to_spawn += self.options.rate * dt;
+            let count = self.to_spawn.floor();
+            self.to_spawn -= count;
+            self.burst(count as usize);
+        } else {
+            self.to_spawn = 0.0;
+        }
+    }
+
+    fn spawn(&mut self) -> Particle {
+        let o = &self.options;
+        let rng = &mut self.rng;
+        let (aw, ah) = o.spawn_area;
+        let angle = rng.between(o.direction);
+        let speed = rng.between(o.speed);
+        Particle {
+            pos: (self.x + (rng.next_float() - 0.5) * aw,
+                  self.y + (rng.next_float() - 0.5) * ah),
+            vel: (angle.cos() * speed, angle.sin() * speed),
+            age: 0.0,
+            lifetime: rng.between(o.lifetime),
+            rotation: rng.between(o.rotation),
+            spin: rng.between(o.spin),
+        }
+    }
+
+    /// Returns the color, size and rotation of a particle.
+    fn look(&self, p: &Particle) -> ([f32; 4], Float, Float) {
+        let t = if p.lifetime > 0.0 { p.age / p.lifetime } else { 1.0 };
+        let color = over_life(&self.options.colors, t, [1.0, 1.0, 1.0, 1.0], |a, b, f| {
+            [lerp(a[0], b[0], f), lerp(a[1], b[1], f), lerp(a[2], b[2], f), lerp(a[3], b[3], f)]
+        });
+        let size = over_life(&self.options.sizes, t, 1.0, lerp);
+        (color, size, p.rotation)
+    }
+}
+
+impl <'a> Drawable for &'a ParticleEmitter {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        if self.particles.is_empty() {
+            return Ok(());
+        }
+
+        match self.options.shape {
+            ParticleShape::Sprite(ref sprite) => {
+                let bounds = sprite.bounds();
+                let mut vertices = Vec::with_capacity(self.particles.len() * 4);
+                let mut idxs = Vec::with_capacity(self.particles.len() * 6);
+                for p in &self.particles {
+                    let (color, size, rotation) = self.look(p);
+                    let corners = quad(p.pos, size, rotation);
+                    let base = vertices.len() as Idx;
+                    for i in 0 .. 4 {
+                        vertices.push(TexVertex {
+                            pos: corners[i],
+                            tex_coords: bounds[i],
+                            color: color,
+                        });
+                    }
+                    idxs.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+                }
+                canvas.draw_tex(TrianglesList, &vertices[..], Some(&idxs[..]), None,
+                                sprite.texture(), None, Some(sprite.sampler()))
+            }
+            ParticleShape::Square => {
+                let mut vertices = Vec::with_capacity(self.particles.len() * 4);
+                let mut idxs = Vec::with_capacity(self.particles.len() * 6);
+                for p in &self.particles {
+                    let (color, size, rotation) = self.look(p);
+                    let base = vertices.len() as Idx;
+                    for &corner in &quad(p.pos, size, rotation) {
+                        vertices.push(ColorVertex { pos: corner, color: color });
+                    }
+                    idxs.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+                }
+                canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), None)
+            }
+            ParticleShape::Circle(segments) => {
+                let segments = if segments < 3 { 3 } else { segments };
+                // The unit circle is worked out once and scaled for every
+                // particle.
+                let unit: Vec<(Float, Float)> = (0 .. segments).map(|i| {
+                    let angle = i as Float / segments as Float * 2.0 * PI;
+                    (angle.cos(), angle.sin())
+                }).collect();
+
+                let per = segments as usize + 1;
+                let mut vertices = Vec::with_capacity(self.particles.len() * per);
+                let mut idxs = Vec::with_capacity(self.particles.len() * segments as usize * 3);
+                for p in &self.particles {
+                    let (color, size, _) = self.look(p);
+                    let radius = size / 2.0;
+                    let center = vertices.len() as Idx;
+                    vertices.push(ColorVertex { pos: [p.pos.0, p.pos.1], color: color });
+                    for &(cx, cy) in &unit {
+                        vertices.push(ColorVertex {
+                            pos: [p.pos.0 + cx * radius, p.pos.1 + cy * radius],
+                            color: color,
+                        });
+                    }
+                    for i in 0 .. segments {
+                        let next = (i + 1) % segments;
+                        idxs.extend_from_slice(&[center, center + 1 + i, center + 1 + next]);
+                    }
+                }
+                canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), None)
+            }
+        }
+    }
+}
+
+/// Returns the corners of a square centered on `pos` in the same order as
+/// `Sprite::bounds`.
+fn quad(pos: (Float, Float), size: Float, rotation: Float) -> [[Float; 2]; 4] {
+    let half = size / 2.0;
+    let (s, c) = rotation.sin_cos();
+    let corner = |x: Float, y: Float| [pos.0 + c * x - s * y, pos.1 + s * x + c * y];
+    [corner(-half, -half), corner(half, -half), corner(-half, half), corner(half, half)]
+}
+
+fn lerp(a: Float, b: Float, t: Float) -> Float {
+    a + (b - a) * t
+}
+
+/// Blends between values that are spread evenly over `t` from `0.0` to
+/// `1.0`.
+fn over_life<T: Copy, F>(values: &[T], t: Float, default: T, blend: F) -> T
+where F: Fn(T, T, Float) -> T {
+    match values.len() {
+        0 => default,
+        1 => values[0],
+        n => {
+            let pos = t.max(0.0).min(1.0) * (n - 1) as Float;
+            let i = cmp::min(pos.floor() as usize, n - 2);
+            blend(values[i], values[i + 1], pos - i as Float)
+        }
+    }
+}
+
+/// A small, fast random number generator.  Particles don't need anything
+/// better, and this keeps emitters reproducible with `set_seed`.
+#[derive(Copy, Clone, Debug)]
+struct XorShift {
+    state: u32,
+}
+
+impl XorShift {
+    fn new(seed: u32) -> XorShift {
+        // A state of zero would only ever produce zeros.
+        XorShift { state: if seed == 0 { 0x9E3779B9 } else { seed } }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Returns a number from `0.0` up to but not including `1.0`.
+    fn next_float(&mut self) -> Float {
+        (self.next_u32() >> 8) as Float / (1u32 << 24) as Float
+    }
+
+    fn between(&mut self, (min, max): (Float, Float)) -> Float {
+        min + (max - min) * self.next_float()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{over_life, lerp, EmitterOptions, ParticleEmitter};
+    use super::super::types::Float;
+
+    fn assert_near(a: Float, b: Float) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    /// Particles that stand still and live for exactly one second.
+    fn still() -> EmitterOptions {
+        EmitterOptions {
+            rate: 0.0,
+            speed: (0.0, 0.0),
+            lifetime: (1.0, 1.0),
+            .. Default::default()
+        }
+    }
+
+    #[test]
+    fn particles_die_after_their_lifetime() {
+        let mut emitter = ParticleEmitter::new(still());
+        emitter.burst(3);
+        emitter.update(0.5);
+        assert_eq!(emitter.len(), 3);
+        emitter.update(0.5);
+        assert!(emitter.is_empty());
+    }
+
+    #[test]
+    fn gravity_and_drag_change_velocity() {
+        let mut emitter = ParticleEmitter::new(EmitterOptions {
+            gravity: (0.0, 10.0),
+            .. still()
+        });
+        emitter.burst(1);
+        emitter.update(0.5);
+        let p = emitter.particles[0];
+        assert_near(p.vel.1, 5.0);
+        assert_near(p.pos.1, 2.5);
+
+        let mut emitter = ParticleEmitter::new(EmitterOptions {
+            speed: (10.0, 10.0),
+            direction: (0.0, 0.0),
+            drag: 0.5,
+            .. still()
+        });
+        emitter.burst(1);
+        emitter.update(0.5);
+        let p = emitter.particles[0];
+        assert_near(p.vel.0, 7.5);
+        assert_near(p.vel.1, 0.0);
+        assert_near(p.pos.0, 3.75);
+    }
+
+    #[test]
+    fn fractions_of_particles_carry_over() {
+        let mut emitter = ParticleEmitter::new(EmitterOptions { rate: 10.0, .. still() });
+        emitter.update(0.05);
+        assert_eq!(emitter.len(), 0);
+        emitter.update(0.05);
+        assert_eq!(emitter.len(), 1);
+        for _ in 0 .. 8 {
+            emitter.update(0.05);
+        }
+        assert_eq!(emitter.len(), 5);
+    }
+
+    #[test]
+    fn stopped_emitters_forget_fractions() {
+        let mut emitter = ParticleEmitter::new(EmitterOptions { rate: 10.0, .. still() });
+        emitter.update(0.05);
+        emitter.emitting = false;
+        emitter.update(0.05);
+        emitter.emitting = true;
+        emitter.update(0.05);
+        assert_eq!(emitter.len(), 0);
+    }
+
+    #[test]
+    fn bursts_stop_at_max_particles() {
+        let mut emitter = ParticleEmitter::new(EmitterOptions { max_particles: 4, .. still() });
+        emitter.burst(3);
+        emitter.burst(3);
+        assert_eq!(emitter.len(), 4);
+
+        emitter.options.rate = 100.0;
+        emitter.update(0.1);
+        assert_eq!(emitter.len(), 4);
+    }
+
+    #[test]
+    fn seeds_reproduce_particles() {
+        let options = EmitterOptions {
+            lifetime: (1.0, 2.0),
+            spawn_area: (10.0, 10.0),
+            .. Default::default()
+        };
+        let run = |seed| {
+            let mut emitter = ParticleEmitter::new(options.clone());
+            emitter.set_seed(seed);
+            for _ in 0 .. 10 {
+                emitter.update(0.1);
+            }
+            emitter.particles
+        };
+        assert_eq!(run(7), run(7));
+        assert!(run(7) != run(8));
+    }
+
+    #[test]
+    fn over_life_blends_between_values() {
+        let none: [Float; 0] = [];
+        for &t in &[0.0, 0.5, 1.0] {
+            assert_near(over_life(&none, t, 3.0, lerp), 3.0);
+            assert_near(over_life(&[2.0], t, 3.0, lerp), 2.0);
+        }
+
+        let values = [0.0, 10.0, 30.0];
+        assert_near(over_life(&values, 0.0, 3.0, lerp), 0.0);
+        assert_near(over_life(&values, 0.5, 3.0, lerp), 10.0);
+        assert_near(over_life(&values, 0.75, 3.0, lerp), 20.0);
+        assert_near(over_life(&values, 1.0, 3.0, lerp), 30.0);
+        assert_near(over_life(&values, 2.0, 3.0, lerp), 30.0);
+    }
+}
@@ -0,0 +1,421 @@
+use std::f32::consts::PI;
+
+use super::types::Float;
+use super::color::rgb;
+use super::canvas::{Canvas, Drawable, DrawStyle, pixel_scale};
+use super::fill::Fill;
+use super::tessellate::{arc_points, fill_contours, stroke_polyline, Dash, FillRule, LineCap, LineJoin, StrokeStyle};
+use ::LuxResult;
+
+use glium::index::PrimitiveType::TrianglesList;
+
+/// The most segments that a single curve will be flattened into.
+const MAX_CURVE_SEGMENTS: u32 = 1024;
+
+/// A single instruction in a `Path`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PathCommand {
+    /// Starts a new sub-path at (x, y).
+    MoveTo(Float, Float),
+    /// A straight line to (x, y).
+    LineTo(Float, Float),
+    /// A quadratic Bézier curve with control point (cx, cy) that ends at
+    /// (x, y).
+    QuadTo(Float, Float, Float, Float),
+    /// A cubic Bézier curve with control points (c1x, c1y) and (c2x, c2y)
+    /// that ends at (x, y).
+    CubicTo(Float, Float, Float, Float, Float, Float),
+    /// An arc of a given radius that is tangent to the line from the
+    /// current point to (x1, y1) and to the line from (x1, y1) to (x2, y2).
+    ArcTo(Float, Float, Float, Float, Float),
+    /// Connects the current point back to the start of the sub-path.
+    Close,
+}
+
+/// A flattened sub-path of a `Path`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Contour {
+    /// The points along the sub-path.
+    pub points: Vec<(Float, Float)>,
+    /// True if the sub-path was closed with `close()`.
+    pub closed: bool,
+}
+
+/// A series of lines and curves that can be filled or stroked.
+///
+/// Paths are built up by chaining calls:
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::graphics::Path;
+///# fn main() {
+/// let mut path = Path::new();
+/// path.move_to(0.0, 0.0)
+///     .line_to(100.0, 0.0)
+///     .quad_to(150.0, 50.0, 100.0, 100.0)
+///     .close();
+///# }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    /// Creates an empty path.
+    pub fn new() -> Path {
+        Path { commands: vec![] }
+    }
+
+    /// Starts a new sub-path at (x, y).
+    pub fn move_to(&mut self, x: Float, y: Float) -> &mut Path {
+        self.commands.push(PathCommand::MoveTo(x, y));
+        self
+    }
+
+    /// Adds a straight line from the current point to (x, y).
+    pub fn line_to(&mut self, x: Float, y: Float) -> &mut Path {
+        self.commands.push(PathCommand::LineTo(x, y));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve with control point (cx, cy) that ends at
+    /// (x, y).
+    pub fn quad_to(&mut self, cx: Float, cy: Float, x: Float, y: Float) -> &mut Path {
+        self.commands.push(PathCommand::QuadTo(cx, cy, x, y));
+        self
+    }
+
+    /// Adds a cubic Bézier curve with control points (c1x, c1y) and
+    /// (c2x, c2y) that ends at (x, y).
+    pub fn cubic_to(&mut self, c1x: Float, c1y: Float, c2x: Float, c2y: Float, x: Float, y: Float)
+    -> &mut Path {
+        self.commands.push(PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y));
+        self
+    }
+
+    /// Adds an arc that rounds off the corner at (x1, y1) between the
+    /// current point and (x2, y2).
+    ///
+    /// A straight line is added from the current point to the start of the
+    /// arc.  If the three points are in a line, or the radius is zero, this
+    /// is the same as `line_to(x1, y1)`.
+    pub fn arc_to(&mut self, x1: Float, y1: Float, x2: Float, y2: Float, radius: Float) -> &mut Path {
+        self.commands.push(PathCommand::ArcTo(x1, y1, x2, y2, radius));
+        self
+    }
+
+    /// Closes the current sub-path by connecting it back to its start.
+    pub fn close(&mut self) -> &mut Path {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Returns the commands that make up this path.
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands[..]
+    }
+
+    /// Converts every curve in the path into straight lines.
+    ///
+    /// `tolerance` is the largest distance that a line is allowed to stray
+    /// from the curve that it approximates.
+    pub fn flatten(&self, tolerance: Float) -> Vec<Contour> {
+        let tolerance = tolerance.max(0.001);
+        let mut contours = vec![];
+        let mut current: Vec<(Float, Float)> = vec![];
+        let mut start = (0.0, 0.0);
+
+        fn finish(current: &mut Vec<(Float, Float)>, closed: bool, contours: &mut Vec<Contour>) {
+            if current.len() > 1 {
+                contours.push(Contour { points: current.clone(), closed: closed });
+            }
+            current.clear();
+        }
+
+        for &command in &self.commands {
+            // Drawing commands without a current point start a new sub-path.
+            let pen = match (current.last().cloned(), command) {
+                (Some(p), _) => p,
+                (None, PathCommand::MoveTo(..)) | (None, PathCommand::Close) => (0.0, 0.0),
+                (None, PathCommand::LineTo(x, y)) |
+                (None, PathCommand::QuadTo(x, y, _, _)) |
+                (None, PathCommand::CubicTo(x, y, _, _, _, _)) |
+                (None, PathCommand::ArcTo(x, y, _, _, _)) => {
+                    start = (x, y);
+                    current.push(start);
+                    start
+                }
+            };
+
+            match command {
+                PathCommand::MoveTo(x, y) => {
+                    finish(&mut current, false, &mut contours);
+                    start = (x, y);
+                    current.push(start);
+                }
+                PathCommand::LineTo(x, y) => {
+                    if pen != (x, y) {
+                        current.push((x, y));
+                    }
+                }
+                PathCommand::QuadTo(cx, cy, x, y) => {
+                    flatten_quad(pen, (cx, cy), (x, y), tolerance, &mut current);
+                }
+                PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                    flatten_cubic(pen, (c1x, c1y), (c2x, c2y), (x, y), tolerance, &mut current);
+                }
+                PathCommand::ArcTo(x1, y1, x2, y2, radius) => {
+                    flatten_arc_to(pen, (x1, y1), (x2, y2), radius, tolerance, &mut current);
+                }
+                PathCommand::Close => {
+                    finish(&mut current, true, &mut contours);
+                    current.push(start);
+                }
+            }
+        }
+        finish(&mut current, false, &mut contours);
+
+        contours
+    }
+}
+
+fn distance(a: (Float, Float), b: (Float, Float)) -> Float {
+    ((a.0 - b.0) * (a.0 - b.0) + (a.1 - b.1) * (a.1 - b.1)).sqrt()
+}
+
+fn segment_count(estimate: Float) -> u32 {
+    (estimate.ceil() as u32).max(1).min(MAX_CURVE_SEGMENTS)
+}
+
+fn flatten_quad(p0: (Float, Float), p1: (Float, Float), p2: (Float, Float),
+                tolerance: Float, out: &mut Vec<(Float, Float)>) {
+    let dd = distance((p0.0 - p1.0, p0.1 - p1.1), (p1.0 - p2.0, p1.1 - p2.1));
+    let n = segment_count((dd / (4.0 * tolerance)).sqrt());
+    for i in 1 .. n + 1 {
+        let t = i as Float / n as Float;
+        let mt = 1.0 - t;
+        out.push((mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+                  mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1));
+    }
+}
+
+fn flatten_cubic(p0: (Float, Float), p1: (Float, Float), p2: (Float, Float), p3: (Float, Float),
+                 tolerance: Float, out: &mut Vec<(Float, Float)>) {
+    let dd1 = distance((p0.0 - p1.0, p0.1 - p1.1), (p1.0 - p2.0, p1.1 - p2.1));
+    let dd2 = distance((p1.0 - p2.0, p1.1 - p2.1), (p2.0 - p3.0, p2.1 - p3.1));
+    let n = segment_count((0.75 * dd1.max(dd2) / tolerance).sqrt());
+    for i in 1 .. n + 1 {
+        let t = i as Float / n as Float;
+        let mt = 1.0 - t;
+        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+        out.push((a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                  a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1));
+    }
+}
+
+fn flatten_arc_to(p0: (Float, Float), p1: (Float, Float), p2: (Float, Float), radius: Float,
+                  tolerance: Float, out: &mut Vec<(Float, Float)>) {
+    let (d0, d2) = (distance(p0, p1), distance(p2, p1));
+    if radius <= 0.0 || d0 == 0.0 || d2 == 0.0 {
+        out.push(p1);
+        return;
+    }
+
+    let v1 = ((p0.0 - p1.0) / d0, (p0.1 - p1.1) / d0);
+    let v2 = ((p2.0 - p1.0) / d2, (p2.1 - p1.1) / d2);
+    let cos = (v1.0 * v2.0 + v1.1 * v2.1).max(-1.0).min(1.0);
+    let angle = cos.acos();
+    if angle < 1e-4 || PI - angle < 1e-4 {
+        out.push(p1);
+        return;
+    }
+
+    let tangent_dist = radius / (angle / 2.0).tan();
+    let center_dist = radius / (angle / 2.0).sin();
+    let (bx, by) = (v1.0 + v2.0, v1.1 + v2.1);
+    let b_len = (bx * bx + by * by).sqrt();
+    let center = (p1.0 + bx / b_len * center_dist, p1.1 + by / b_len * center_dist);
+
+    let t1 = (p1.0 + v1.0 * tangent_dist, p1.1 + v1.1 * tangent_dist);
+    let t2 = (p1.0 + v2.0 * tangent_dist, p1.1 + v2.1 * tangent_dist);
+
+    let start_angle = (t1.1 - center.1).atan2(t1.0 - center.0);
+    let end_angle = (t2.1 - center.1).atan2(t2.0 - center.0);
+    let mut sweep = end_angle - start_angle;
+    while sweep > PI { sweep -= 2.0 * PI; }
+    while sweep < -PI { sweep += 2.0 * PI; }
+
+    out.push(t1);
+    arc_points(center, radius, start_angle, sweep, tolerance, out);
+}
+
+#[derive(Copy, Clone)]
+pub struct PathShape<'a> {
+    pub path: Option<&'a Path>,
+    pub color: [f32; 4],
+    pub fill: Option<Fill>,
+    pub style: DrawStyle,
+    pub fill_rule: FillRule,
+    pub stroke_width: Float,
+    pub stroke_color: [f32; 4],
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: Float,
+    pub dash: Option<Dash>,
+    pub tolerance: Float,
+    pub transform: Option<[[Float; 4]; 4]>,
+}
+
+impl Default for PathShape<'static> {
+    fn default() -> PathShape<'static> {
+        PathShape {
+            path: None,
+            color: rgb(0.0, 0.0, 0.0),
+            fill: None,
+            style: DrawStyle::Fill,
+            fill_rule: FillRule::NonZero,
+            stroke_width: 1.0,
+            stroke_color: rgb(0.0, 0.0, 0.0),
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash: None,
+            tolerance: 0.25,
+            transform: None,
+        }
+    }
+}
+
+impl <'a> Drawable for PathShape<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let path = match self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let contours = path.flatten(self.tolerance);
+        let mut vertices = vec![];
+        let mut idxs = vec![];
+
+        if self.style.fills() {
+            let start = (vertices.len(), idxs.len());
+            let points: Vec<_> = contours.iter().map(|c| c.points.clone()).collect();
+            fill_contours(&points[..], self.fill_rule, self.color, &mut vertices, &mut idxs);
+            if let Some(fill) = self.fill {
+                fill.paint(start, pixel_scale(canvas, self.transform), &mut vertices, &mut idxs);
+            }
+        }
+
+        if self.style.strokes() {
+            let stroke = StrokeStyle {
+                width: self.stroke_width,
+                join: self.join,
+                cap: self.cap,
+                miter_limit: self.miter_limit,
+                dash: self.dash,
+            };
+            for contour in &contours {
+                stroke_polyline(&contour.points[..], contour.closed, &stroke,
+                                self.stroke_color, &mut vertices, &mut idxs);
+            }
+        }
+
+        if idxs.is_empty() {
+            return Ok(());
+        }
+        canvas.draw_colored(TrianglesList, &vertices[..], Some(&idxs[..]), self.transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distance, Path};
+    use super::super::types::Float;
+
+    fn quad_at(p: [(Float, Float); 3], t: Float) -> (Float, Float) {
+        let mt = 1.0 - t;
+        (mt * mt * p[0].0 + 2.0 * mt * t * p[1].0 + t * t * p[2].0,
+         mt * mt * p[0].1 + 2.0 * mt * t * p[1].1 + t * t * p[2].1)
+    }
+
+    #[test]
+    fn lines_are_kept_as_they_are() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0).line_to(10.0, 0.0).line_to(10.0, 5.0);
+        let contours = path.flatten(0.25);
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].points, vec![(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)]);
+        assert!(!contours[0].closed);
+    }
+
+    #[test]
+    fn move_to_and_close_split_contours() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0).line_to(1.0, 0.0).line_to(1.0, 1.0).close()
+            .move_to(5.0, 5.0).line_to(6.0, 5.0)
+            .move_to(9.0, 9.0);
+        let contours = path.flatten(0.25);
+        assert_eq!(contours.len(), 2);
+        assert!(contours[0].closed);
+        assert_eq!(contours[1].points, vec![(5.0, 5.0), (6.0, 5.0)]);
+        assert!(!contours[1].closed);
+    }
+
+    #[test]
+    fn commands_without_a_current_point_start_one() {
+        let mut path = Path::new();
+        path.line_to(3.0, 4.0).line_to(5.0, 4.0);
+        assert_eq!(path.flatten(0.25)[0].points, vec![(3.0, 4.0), (5.0, 4.0)]);
+    }
+
+    #[test]
+    fn quads_stay_within_tolerance() {
+        let curve = [(0.0, 0.0), (50.0, 100.0), (100.0, 0.0)];
+        for &tolerance in &[1.0, 0.25, 0.05] {
+            let mut path = Path::new();
+            path.move_to(curve[0].0, curve[0].1)
+                .quad_to(curve[1].0, curve[1].1, curve[2].0, curve[2].1);
+            let points = &path.flatten(tolerance)[0].points;
+            assert_eq!(*points.last().unwrap(), curve[2]);
+
+            // Points are evenly spaced in t, so the curve strays furthest
+            // from each line halfway between its ends.
+            let n = (points.len() - 1) as Float;
+            for (i, line) in points.windows(2).enumerate() {
+                let on_curve = quad_at(curve, (i as Float + 0.5) / n);
+                let mid = ((line[0].0 + line[1].0) / 2.0, (line[0].1 + line[1].1) / 2.0);
+                assert!(distance(on_curve, mid) <= tolerance * 1.01);
+            }
+        }
+    }
+
+    #[test]
+    fn cubics_end_at_the_last_point() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0).cubic_to(0.0, 50.0, 100.0, 50.0, 100.0, 0.0);
+        let coarse = path.flatten(2.0)[0].points.len();
+        let points = &path.flatten(0.1)[0].points;
+        assert_eq!(*points.last().unwrap(), (100.0, 0.0));
+        assert!(points.len() > coarse);
+    }
+
+    #[test]
+    fn arc_to_rounds_the_corner() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0).arc_to(10.0, 0.0, 10.0, 10.0, 2.0);
+        let points = &path.flatten(0.01)[0].points;
+        assert!(distance(points[1], (8.0, 0.0)) < 1e-3);
+        assert!(distance(*points.last().unwrap(), (10.0, 2.0)) < 1e-3);
+        for &p in &points[1 ..] {
+            assert!((distance(p, (8.0, 2.0)) - 2.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn arc_to_in_a_straight_line_is_a_line() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0).arc_to(5.0, 0.0, 10.0, 0.0, 2.0);
+        assert_eq!(path.flatten(0.25)[0].points, vec![(0.0, 0.0), (5.0, 0.0)]);
+    }
+}
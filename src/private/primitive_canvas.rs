@@ -1,12 +1,13 @@
 use std::rc::Rc;
 
 use super::accessors::{DrawLike, DrawFields};
-use super::gfx_integration::{ColorVertex, TexVertex};
+use super::gfx_integration::{ColorVertex, TexVertex, Sampler};
 use glium::index::PrimitiveType;
 use super::color::Color;
 use super::gfx_integration;
 use super::types::{Idx, Float};
 use super::error::LuxResult;
+use super::affine::Affine2;
 
 use vecmath;
 use glium;
@@ -15,7 +16,7 @@ use poison_pool;
 // TODO: Come up with a better name for this enum and varients.
 /// When modifying a stencil or clearing the stencil buffer,
 /// operations require a StencilType.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum StencilType {
     /// Blacklists pixels on the screen when drawing.
     Deny,
@@ -24,7 +25,7 @@ pub enum StencilType {
 }
 
 /// Signifies what state we are in with regards to drawing with stencils.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum StencilState {
     /// We are currently drawing into the stencil buffer.
     DrawingStencil(StencilType),
@@ -36,7 +37,16 @@ pub enum StencilState {
 
 pub struct DrawParamModifier {
     pub scissor: Option<(u32, u32, u32, u32)>,
-    pub stencil_state: StencilState
+    pub stencil_state: StencilState,
+    pub state_stack: Vec<SavedState>,
+}
+
+/// The drawing state that is saved by `Canvas::push_state`.
+#[derive(Clone, Copy)]
+pub struct SavedState {
+    pub matrix: [[Float; 4]; 4],
+    pub scissor: Option<(u32, u32, u32, u32)>,
+    pub stencil_state: StencilState,
 }
 
 /// A cache for batching texture drawing commands.
@@ -65,10 +75,8 @@ pub struct CachedTexDraw {
     pub texture: Rc<glium::texture::Texture2d>,
     /// A cache of indices indexing into the points cache.
     pub idxs: poison_pool::Item<Vec<Idx>>,
-    /// A color that will be multiplied against the values in the texture
-    /// to give the texture color.
-    pub color_mult: [Float; 4],
-
+    /// The settings used to sample the texture.
+    pub sampler: Sampler,
 }
 
 /// A Primitive canvas is a trait that is implemented by objects that
@@ -121,13 +129,20 @@ pub trait PrimitiveCanvas: DrawLike {
                            mat: Option<[[Float; 4]; 4]>) -> LuxResult<()>;
 
     /// Same as `draw_colored` but for textured vertices.
+    ///
+    /// color_mult: An optional color that is multiplied against the color of
+    ///             each vertex.  Draws with different colors can still be
+    ///             batched together.
+    /// sampler: The settings used to read from the texture.  Draws that use
+    ///          different samplers are not batched together.
     fn draw_tex(&mut self,
                 typ: PrimitiveType,
                 vs: &[TexVertex],
                 idxs: Option<&[Idx]>,
                 mat: Option<[[Float; 4]; 4]>,
                 Rc<glium::texture::Texture2d>,
-                color_mult: Option<[Float; 4]>) -> LuxResult<()>;
+                color_mult: Option<[Float; 4]>,
+                sampler: Option<Sampler>) -> LuxResult<()>;
 
     /// Same as `draw_colored_now` but for textured vertices.
     fn draw_textured_now(&mut self,
@@ -136,7 +151,7 @@ pub trait PrimitiveCanvas: DrawLike {
                 idxs: Option<&[Idx]>,
                 base_mat: Option<[[Float; 4]; 4]>,
                 texture: &glium::texture::Texture2d,
-                color_mult: [Float; 4]) -> LuxResult<()>;
+                sampler: Sampler) -> LuxResult<()>;
 
     /// Same as `draw_colored_no_batch` but for textured vertices.
     fn draw_tex_no_batch(&mut self,
@@ -145,7 +160,8 @@ pub trait PrimitiveCanvas: DrawLike {
                          idxs: Option<&[Idx]>,
                          mat: Option<[[Float; 4]; 4]>,
                          &glium::texture::Texture2d,
-                         color_mult: Option<[Float; 4]>) -> LuxResult<()>;
+                         color_mult: Option<[Float; 4]>,
+                         sampler: Option<Sampler>) -> LuxResult<()>;
 
     /// Flush all stored draw calls to the screen.
     ///
@@ -154,6 +170,24 @@ pub trait PrimitiveCanvas: DrawLike {
     fn flush_draw(&mut self) -> LuxResult<()>;
 }
 
+/// Multiplies two colors together component-wise.
+fn tint(color: [Float; 4], mult: [Float; 4]) -> [Float; 4] {
+    [color[0] * mult[0], color[1] * mult[1], color[2] * mult[2], color[3] * mult[3]]
+}
+
+/// Combines the canvas matrix with the transform of a draw into the 2x3
+/// matrix that batched vertices are multiplied by.
+///
+/// Only the x and y outputs of the 4x4 product are kept, the same as when
+/// vertices were multiplied by the full matrix, so depth and perspective
+/// terms have no effect.
+fn batch_matrix(matrix: [[Float; 4]; 4], transform: Option<[[Float; 4]; 4]>) -> Affine2 {
+    match transform {
+        Some(t) => Affine2::from(vecmath::col_mat4_mul(matrix, t)),
+        None => Affine2::from(matrix),
+    }
+}
+
 fn draw_params<C: DrawLike>(c: &C) -> glium::DrawParameters<'static> {
         use glium::draw_parameters::{StencilOperation, StencilTest};
         let defaults: glium::DrawParameters = ::std::default::Default::default();
@@ -250,7 +284,8 @@ impl DrawParamModifier {
     pub fn new() -> DrawParamModifier {
         DrawParamModifier {
             scissor: None,
-            stencil_state: StencilState::None
+            stencil_state: StencilState::None,
+            state_stack: vec![],
         }
     }
 }
@@ -303,7 +338,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
                 idxs: Option<&[Idx]>,
                 base_mat: Option<[[Float; 4]; 4]>,
                 texture: &glium::texture::Texture2d,
-                color_mult: [Float; 4]) -> LuxResult<()> {
+                sampler: Sampler) -> LuxResult<()> {
         use glium::{Surface, IndexBuffer};
         use glium::index::NoIndices;
 
@@ -315,7 +350,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
         let uniform = gfx_integration::TexParams {
             matrix: base_mat.unwrap_or(vecmath::mat4_id()),
             tex: texture,
-            color_mult: color_mult
+            sampler: sampler,
         };
 
 
@@ -340,8 +375,8 @@ impl <T: DrawLike> PrimitiveCanvas for T
                 first_result = Some(self.draw_colored_now(typ, &points, Some(&idxs), None));
         }
 
-        if let Some(CachedTexDraw{typ, points, texture, idxs, color_mult}) = self.draw_fields().tex_draw_cache.take() {
-                second_result = Some(self.draw_textured_now(typ, &points, Some(&idxs), None, &*texture, color_mult));
+        if let Some(CachedTexDraw{typ, points, texture, idxs, sampler}) = self.draw_fields().tex_draw_cache.take() {
+                second_result = Some(self.draw_textured_now(typ, &points, Some(&idxs), None, &*texture, sampler));
         }
 
         match (first_result, second_result) {
@@ -378,15 +413,24 @@ impl <T: DrawLike> PrimitiveCanvas for T
                            idxs: Option<&[Idx]>,
                            transform: Option<[[Float; 4]; 4]>,
                            texture: &glium::texture::Texture2d,
-                           color_mult: Option<[Float; 4]>) -> LuxResult<()> {
+                           color_mult: Option<[Float; 4]>,
+                           sampler: Option<Sampler>) -> LuxResult<()> {
         try!(self.flush_draw());
         let matrix = *self.draw_fields().matrix;
         let transform = match transform {
             Some(t) => vecmath::col_mat4_mul(matrix, t),
             None => matrix,
         };
-        let color_mult = color_mult.unwrap_or([1.0, 1.0, 1.0, 1.0]);
-        self.draw_textured_now(n_typ, n_points, idxs, Some(transform), texture, color_mult)
+        let sampler = sampler.unwrap_or(Sampler::default());
+        match color_mult {
+            Some(color_mult) => {
+                let tinted: Vec<_> = n_points.iter().map(|&point| {
+                    TexVertex { color: tint(point.color, color_mult), .. point }
+                }).collect();
+                self.draw_textured_now(n_typ, &tinted[..], idxs, Some(transform), texture, sampler)
+            }
+            None => self.draw_textured_now(n_typ, n_points, idxs, Some(transform), texture, sampler)
+        }
     }
 
 
@@ -396,7 +440,8 @@ impl <T: DrawLike> PrimitiveCanvas for T
                   idxs: Option<&[Idx]>,
                   transform: Option<[[Float; 4]; 4]>,
                   texture: Rc<glium::texture::Texture2d>,
-                  color_mult: Option<[Float; 4]>) -> LuxResult<()> {
+                  color_mult: Option<[Float; 4]>,
+                  sampler: Option<Sampler>) -> LuxResult<()> {
         use glium::index::PrimitiveType::{Points, LinesList, TrianglesList};
         use std::mem::transmute;
 
@@ -408,13 +453,14 @@ impl <T: DrawLike> PrimitiveCanvas for T
         //let DrawFields{color_draw_cache, tex_draw_cache, matrix, ..} = self.draw_fields();
 
         let color_mult = color_mult.unwrap_or([1.0, 1.0, 1.0, 1.0]);
+        let sampler = sampler.unwrap_or(Sampler::default());
 
         // Look at all this awful code for handling something that should
         // be dead simple!
         if self.draw_fields().tex_draw_cache.is_some() {
             let same_type;
             let coherant_group;
-            let same_color_mult;
+            let same_sampler;
             let same_tex;
             {
                 let tex_draw_cache = self.draw_fields().tex_draw_cache.as_ref().unwrap();
@@ -423,14 +469,14 @@ impl <T: DrawLike> PrimitiveCanvas for T
                     Points | LinesList | TrianglesList => true,
                     _ => false
                 };
-                same_color_mult = tex_draw_cache.color_mult == color_mult;
+                same_sampler = tex_draw_cache.sampler == sampler;
 
                 let our_ptr: *mut () = unsafe {transmute(&*tex_draw_cache.texture)};
                 let otr_ptr: *mut () = unsafe {transmute(&*texture)};
                 same_tex = our_ptr == otr_ptr;
             }
 
-            if !same_type || !coherant_group || !same_color_mult || !same_tex {
+            if !same_type || !coherant_group || !same_sampler || !same_tex {
                 try!(self.flush_draw());
                 *self.draw_fields().tex_draw_cache = Some(CachedTexDraw {
                     typ: n_typ,
@@ -438,7 +484,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
                     points: ::poison_pool::Item::from_value(Vec::new()),
                     idxs: ::poison_pool::Item::from_value(Vec::new()),
                     texture: texture,
-                    color_mult: color_mult,
+                    sampler: sampler,
                 });
             }
         } else {
@@ -448,7 +494,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
                 points: ::poison_pool::Item::from_value(Vec::new()),
                 idxs: ::poison_pool::Item::from_value(Vec::new()),
                 texture: texture,
-                color_mult: color_mult
+                sampler: sampler,
             });
         }
 
@@ -457,8 +503,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
                 "The length of the indexes array must be a multiple of three.");
         }
 
-        let transform = transform.unwrap_or(vecmath::mat4_id());
-        let mat = vecmath::col_mat4_mul(*self.draw_fields().matrix, transform);
+        let mat = batch_matrix(*self.draw_fields().matrix, transform);
         let draw_cache = self.draw_fields().tex_draw_cache.as_mut().unwrap();
 
         let already_in = draw_cache.points.len() as Idx;
@@ -467,10 +512,9 @@ impl <T: DrawLike> PrimitiveCanvas for T
         // Perform the global transforms here
         draw_cache.points.extend(n_points.iter().map(|&point| {
             let mut point = point.clone();
-            let res = vecmath::col_mat4_transform(
-                mat,
-                [point.pos[0], point.pos[1], 0.0, 1.0]);
-            point.pos = [res[0], res[1]];
+            let (x, y) = mat.transform_point((point.pos[0], point.pos[1]));
+            point.pos = [x, y];
+            point.color = tint(point.color, color_mult);
             point
         }));
 
@@ -538,8 +582,7 @@ impl <T: DrawLike> PrimitiveCanvas for T
                 "The length of the indexes array must be a multiple of three.");
         }
 
-        let transform = transform.unwrap_or(vecmath::mat4_id());
-        let mat = vecmath::col_mat4_mul(*self.draw_fields().matrix, transform);
+        let mat = batch_matrix(*self.draw_fields().matrix, transform);
         let draw_cache = self.draw_fields().color_draw_cache.as_mut().unwrap();
 
         let already_in = draw_cache.points.len() as Idx;
@@ -548,10 +591,8 @@ impl <T: DrawLike> PrimitiveCanvas for T
         // Perform the global transforms here
         draw_cache.points.extend(n_points.iter().map(|&point| {
             let mut point = point.clone();
-            let res = vecmath::col_mat4_transform(
-                mat,
-                [point.pos[0], point.pos[1], 0.0, 1.0]);
-            point.pos = [res[0], res[1]];
+            let (x, y) = mat.transform_point((point.pos[0], point.pos[1]));
+            point.pos = [x, y];
             point
         }));
 
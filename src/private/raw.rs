@@ -1,5 +1,6 @@
-use vecmath::{mat4_id, col_mat4_mul};
+use vecmath::{mat4_id, mat4_det, mat4_inv, col_mat4_mul};
 use super::types::Float;
+use super::affine::{corner_bounds, Affine2};
 
 /// A trait for objects that can be "transformed".  Transformations
 /// include scaling, translation, shearing, rotating, and general
@@ -10,49 +11,81 @@ pub trait Transform {
     /// Return a mutible reference to the current matrix.
     fn current_matrix_mut(&mut self) -> &mut [[Float; 4]; 4];
 
+    /// Return the matrix that the current matrix started out as before any
+    /// transformations were applied.
+    ///
+    /// Canvases start out with a matrix that turns pixels into the
+    /// coordinates that the graphics card expects.  `transform_point` and
+    /// friends undo this matrix so that their results are in pixels.
+    fn base_matrix(&self) -> [[Float; 4]; 4] {
+        mat4_id()
+    }
+
+    /// Returns the matrix that undoes every transformation applied so far,
+    /// taking canvas pixels back to local coordinates, or `None` if it
+    /// can't be inverted (for example after scaling by zero).
+    ///
+    /// Like `inverse_transform_point`, this leaves out `base_matrix`.
+    fn inverse(&self) -> Option<[[Float; 4]; 4]> {
+        local_to_base(self).and_then(invert)
+    }
+
+    /// Transforms a point by every transformation applied so far.
+    ///
+    /// On a canvas this turns a point in the current local coordinates into
+    /// a position on the canvas in pixels.
+    fn transform_point(&self, point: (Float, Float)) -> (Float, Float) {
+        match local_to_base(self) {
+            Some(m) => apply_to_point(m, point),
+            None => point,
+        }
+    }
+
+    /// The opposite of `transform_point`.  Turns a position on the canvas,
+    /// like the mouse position, into the current local coordinates.
+    ///
+    /// Returns `None` if the transformations can't be undone.
+    fn inverse_transform_point(&self, point: (Float, Float)) -> Option<(Float, Float)> {
+        local_to_base(self).and_then(invert).map(|m| apply_to_point(m, point))
+    }
+
+    /// Transforms the corners of a rectangle given as (x, y, w, h) and
+    /// returns the smallest axis-aligned rectangle that contains them.
+    fn transform_rect(&self, rect: (Float, Float, Float, Float))
+    -> (Float, Float, Float, Float) {
+        corner_bounds(rect, |p| self.transform_point(p))
+    }
+
     /// Multiplies the current matrix against another.
     /// `self = self * other`.
-    fn apply_matrix(&mut self, other: [[Float; 4]; 4]) -> &mut Self{
+    ///
+    /// `other` can be a 4x4 matrix or an `Affine2`.
+    fn apply_matrix<M: Into<[[Float; 4]; 4]>>(&mut self, other: M) -> &mut Self{
         {
             let current = self.current_matrix_mut();
-            *current = col_mat4_mul(*current, other);
+            *current = col_mat4_mul(*current, other.into());
         }
         self
     }
 
     /// Applies a translation transformation to the matrix.
     fn translate(&mut self, dx: Float, dy: Float) -> &mut Self {
-        let mut prod = mat4_id();
-        prod[3][0] = dx;
-        prod[3][1] = dy;
-        self.apply_matrix(prod)
+        self.apply_matrix(Affine2::translation(dx, dy))
     }
 
     /// Applies a scaling transformation to the matrix.
     fn scale(&mut self, sx: Float, sy: Float) -> &mut Self {
-        let mut prod = mat4_id();
-        prod[0][0] = sx;
-        prod[1][1] = sy;
-        self.apply_matrix(prod)
+        self.apply_matrix(Affine2::scaling(sx, sy))
     }
 
     /// Applies a shearing transformation to the matrix.
     fn shear(&mut self, sx: Float, sy: Float) -> &mut Self {
-        let mut prod = mat4_id();
-        prod[1][0] = sx;
-        prod[0][1] = sy;
-        self.apply_matrix(prod)
+        self.apply_matrix(Affine2::shearing(sx, sy))
     }
 
     /// Applies a rotation transformation to the matrix.
     fn rotate(&mut self, theta: Float) -> &mut Self {
-        let mut prod = mat4_id();
-        let (c, s) = (theta.cos(), theta.sin());
-        prod[0][0] = c;
-        prod[0][1] = s;
-        prod[1][0] = -s;
-        prod[1][1] = c;
-        self.apply_matrix(prod)
+        self.apply_matrix(Affine2::rotation(theta))
     }
 
     /// Combines rotation with translation to effectively
@@ -138,9 +171,97 @@ pub trait Transform {
     }
 }
 
+/// Returns the transformations applied on top of the base matrix.
+fn local_to_base<T: Transform + ?Sized>(t: &T) -> Option<[[Float; 4]; 4]> {
+    let base = t.base_matrix();
+    if base == mat4_id() {
+        return Some(*t.current_matrix());
+    }
+    invert(base).map(|inv| col_mat4_mul(inv, *t.current_matrix()))
+}
+
+fn invert(m: [[Float; 4]; 4]) -> Option<[[Float; 4]; 4]> {
+    let det = mat4_det(m);
+    if det == 0.0 || !det.is_finite() {
+        None
+    } else {
+        Some(mat4_inv(m))
+    }
+}
+
+fn apply_to_point(m: [[Float; 4]; 4], (x, y): (Float, Float)) -> (Float, Float) {
+    let px = m[0][0] * x + m[1][0] * y + m[3][0];
+    let py = m[0][1] * x + m[1][1] * y + m[3][1];
+    let w = m[0][3] * x + m[1][3] * y + m[3][3];
+    if w != 0.0 && w != 1.0 {
+        (px / w, py / w)
+    } else {
+        (px, py)
+    }
+}
+
 /// Turns out that implementing matrix transformations on matrices is a
 /// no brainer!
 impl Transform for [[Float; 4]; 4] {
     fn current_matrix(&self) -> &[[Float; 4]; 4] { self }
     fn current_matrix_mut(&mut self) -> &mut [[Float; 4]; 4] { self }
 }
+
+#[cfg(test)]
+mod tests {
+    use vecmath::mat4_id;
+    use super::{Transform, apply_to_point};
+    use super::super::affine::assert_point_near as assert_near;
+    use super::super::types::Float;
+
+    /// A transform with a base matrix like a canvas, turning 200x100
+    /// pixels into -1..1.
+    struct Target {
+        base: [[Float; 4]; 4],
+        matrix: [[Float; 4]; 4],
+    }
+
+    impl Transform for Target {
+        fn current_matrix(&self) -> &[[Float; 4]; 4] { &self.matrix }
+        fn current_matrix_mut(&mut self) -> &mut [[Float; 4]; 4] { &mut self.matrix }
+        fn base_matrix(&self) -> [[Float; 4]; 4] { self.base }
+    }
+
+    fn target() -> Target {
+        let mut base = mat4_id();
+        base[0][0] = 2.0 / 200.0;
+        base[1][1] = -2.0 / 100.0;
+        base[3][0] = -1.0;
+        base[3][1] = 1.0;
+        Target { base: base, matrix: base }
+    }
+
+    #[test]
+    fn points_ignore_the_base_matrix() {
+        let mut t = target();
+        assert_near(t.transform_point((20.0, 30.0)), (20.0, 30.0));
+        t.translate(10.0, 5.0).scale(2.0, 2.0);
+        assert_near(t.transform_point((1.0, 1.0)), (12.0, 7.0));
+        assert_near(t.inverse_transform_point((12.0, 7.0)).unwrap(), (1.0, 1.0));
+        assert_eq!(t.transform_rect((0.0, 0.0, 3.0, 4.0)), (10.0, 5.0, 6.0, 8.0));
+    }
+
+    #[test]
+    fn inverse_matches_inverse_transform_point() {
+        let mut t = target();
+        t.translate(40.0, -3.0).rotate(0.6).scale(1.5, 0.5);
+        let inverse = t.inverse().unwrap();
+        for &p in &[(0.0, 0.0), (12.0, 7.0), (-50.0, 80.0)] {
+            assert_near(apply_to_point(inverse, p), t.inverse_transform_point(p).unwrap());
+            assert_near(apply_to_point(inverse, t.transform_point(p)), p);
+        }
+    }
+
+    #[test]
+    fn scaling_by_zero_has_no_inverse() {
+        let mut t = target();
+        t.scale(0.0, 1.0);
+        assert!(t.inverse().is_none());
+        assert!(t.inverse_transform_point((1.0, 1.0)).is_none());
+    }
+}
@@ -11,21 +11,24 @@ Result<glium::Program,
                 uniform mat4 matrix;
                 in vec2 pos;
                 in vec2 tex_coords;
+                in vec4 color;
                 out vec2 v_tex_coords;
+                out vec4 v_color;
                 void main() {
                     gl_Position = matrix * vec4(pos, 0.0, 1.0);
                     v_tex_coords = tex_coords;
+                    v_color = color;
                 }
             ",
 
             fragment: "
                 #version 140
                 uniform sampler2D tex;
-                uniform vec4 color_mult;
                 in vec2 v_tex_coords;
+                in vec4 v_color;
                 out vec4 f_color;
                 void main() {
-                    f_color = texture(tex, v_tex_coords) * color_mult;
+                    f_color = texture(tex, v_tex_coords) * v_color;
                 }
             "
         },
@@ -36,20 +39,23 @@ Result<glium::Program,
                 uniform mat4 matrix;
                 attribute vec2 pos;
                 attribute vec2 tex_coords;
+                attribute vec4 color;
                 varying vec2 v_tex_coords;
+                varying vec4 v_color;
                 void main() {
                     gl_Position = matrix * vec4(pos, 0.0, 1.0);
                     v_tex_coords = tex_coords;
+                    v_color = color;
                 }
             ",
 
             fragment: "
                 #version 110
                 uniform sampler2D tex;
-                uniform vec4 color_mult;
                 varying vec2 v_tex_coords;
+                varying vec4 v_color;
                 void main() {
-                    gl_FragColor = texture2D(tex, v_tex_coords) * color_mult;
+                    gl_FragColor = texture2D(tex, v_tex_coords) * v_color;
                 }
             ",
         },
@@ -0,0 +1,469 @@
+use rustc_serialize::json::Json;
+
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::accessors::StateLike;
+use super::error::{LuxError, LuxResult};
+use super::sprite::{IntoSprite, Sprite, NonUniformSpriteSheet};
+use super::types::Float;
+
+/// One frame of a sprite sheet that was exported by TexturePacker or
+/// Aseprite.
+#[derive(Clone, Debug)]
+pub struct SheetFrame {
+    /// The name of the frame, which is also its key in the sprite sheet.
+    pub name: String,
+    /// The top left corner of the frame on the sheet.
+    pub pos: (u32, u32),
+    /// The size of the frame as it is stored on the sheet.  When the frame
+    /// is rotated, this is the width and height of the upright frame
+    /// swapped.
+    pub size: (u32, u32),
+    /// True if the frame is stored on the sheet rotated 90 degrees
+    /// clockwise.
+    pub rotated: bool,
+    /// True if transparent pixels were trimmed off of the edges of the
+    /// frame.
+    pub trimmed: bool,
+    /// Where the trimmed frame sits inside of the original image.
+    pub source_offset: (u32, u32),
+    /// The size of the original image before it was trimmed.
+    pub source_size: (u32, u32),
+    /// The pivot of the original image, relative to its size.
+    pub pivot: Option<(Float, Float)>,
+    /// How long the frame is shown for in milliseconds.
+    pub duration: Option<u32>,
+}
+
+/// The order that the frames of a tag are played in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+}
+
+/// A named range of frames, such as a single animation in an Aseprite
+/// file.
+#[derive(Clone, Debug)]
+pub struct FrameTag {
+    pub name: String,
+    /// The index of the first frame in the tag.
+    pub from: usize,
+    /// The index of the last frame in the tag.
+    pub to: usize,
+    pub direction: TagDirection,
+}
+
+/// The frames and tags read from the JSON that TexturePacker and Aseprite
+/// export alongside a sprite sheet image.
+///
+/// Both the "hash" and the "array" layouts of the `frames` list are
+/// supported.
+#[derive(Clone, Debug)]
+pub struct SheetData {
+    /// The image file that the sheet was exported to, relative to the JSON
+    /// file.
+    pub image: Option<String>,
+    /// Every frame, in the order that they appear in the file.
+    pub frames: Vec<SheetFrame>,
+    pub tags: Vec<FrameTag>,
+}
+
+impl SheetFrame {
+    /// The rotation that undoes the rotation of the frame on the sheet.
+    ///
+    /// Add this to the `rotation` of a `Picture` to draw the frame upright.
+    pub fn rotation(&self) -> Float {
+        if self.rotated { -PI / 2.0 } else { 0.0 }
+    }
+
+    /// The size of the frame when it is drawn upright.
+    pub fn upright_size(&self) -> (u32, u32) {
+        if self.rotated { (self.size.1, self.size.0) } else { self.size }
+    }
+
+    /// The pivot of the frame relative to its size on the sheet.
+    ///
+    /// This accounts for trimming, so a picture of a trimmed frame that is
+    /// placed at its pivot ends up where the untrimmed image would have
+    /// been.  Frames that are neither trimmed nor have a pivot return
+    /// `None`.
+    pub fn sprite_pivot(&self) -> Option<(Float, Float)> {
+        if !self.trimmed && self.pivot.is_none() {
+            return None;
+        }
+
+        let (px, py) = self.pivot.unwrap_or((0.0, 0.0));
+        let (sw, sh) = (self.source_size.0 as Float, self.source_size.1 as Float);
+        let (ox, oy) = (self.source_offset.0 as Float, self.source_offset.1 as Float);
+        let (fw, fh) = self.upright_size();
+        let (fw, fh) = (fw as Float, fh as Float);
+        let (u, v) = ((px * sw - ox) / fw, (py * sh - oy) / fh);
+
+        if self.rotated {
+            // A point on the upright frame moves here when the frame is
+            // rotated clockwise onto the sheet.
+            Some((1.0 - v, u))
+        } else {
+            Some((u, v))
+        }
+    }
+}
+
+impl SheetData {
+    /// Parses sprite sheet JSON that was exported by TexturePacker or
+    /// Aseprite.
+    pub fn from_json(source: &str) -> LuxResult<SheetData> {
+        let root = try!(Json::from_str(source));
+
+        let frames = match try!(field(&root, "frames")) {
+            &Json::Array(ref frames) => {
+                let mut out = vec![];
+                for frame in frames {
+                    let name = try!(string_field(frame, "filename"));
+                    out.push(try!(parse_frame(name, frame)));
+                }
+                out
+            }
+            &Json::Object(ref frames) => {
+                // The parsed object doesn't remember the order of its keys,
+                // and tags refer to frames by their position in the file.
+                let mut names = frame_order(source);
+                if names.len() != frames.len() {
+                    names = frames.keys().cloned().collect();
+                }
+
+                let mut out = vec![];
+                for name in names {
+                    let frame = try!(frames.get(&name).ok_or_else(|| {
+                        bad_sheet(&format!("missing frame `{}`", name))
+                    }));
+                    out.push(try!(parse_frame(name, frame)));
+                }
+                out
+            }
+            _ => return Err(bad_sheet("`frames` must be an object or an array")),
+        };
+
+        let meta = root.find("meta");
+        let image = meta.and_then(|m| m.find("image"))
+                        .and_then(Json::as_string)
+                        .map(String::from);
+
+        let mut tags = vec![];
+        if let Some(list) = meta.and_then(|m| m.find("frameTags")).and_then(Json::as_array) {
+            for tag in list {
+                let direction = match tag.find("direction").and_then(Json::as_string) {
+                    Some("reverse") => TagDirection::Reverse,
+                    Some("pingpong") => TagDirection::PingPong,
+                    _ => TagDirection::Forward,
+                };
+                let tag = FrameTag {
+                    name: try!(string_field(tag, "name")),
+                    from: try!(number_field(tag, "from")) as usize,
+                    to: try!(number_field(tag, "to")) as usize,
+                    direction: direction,
+                };
+                if tag.from > tag.to || tag.to >= frames.len() {
+                    return Err(bad_sheet(&format!("tag `{}` refers to frames that don't exist",
+                                                  tag.name)));
+                }
+                tags.push(tag);
+            }
+        }
+
+        Ok(SheetData {
+            image: image,
+            frames: frames,
+            tags: tags,
+        })
+    }
+
+    /// Builds a sprite sheet that maps every frame name to its sprite.
+    ///
+    /// Trimmed frames and frames with pivots get their pivot set with
+    /// `sprite_pivot`.
+    pub fn sprite_sheet(&self, sprite: &Sprite) -> LuxResult<NonUniformSpriteSheet<String>> {
+        let mut sheet = sprite.as_nonuniform_sprite_sheet();
+        for frame in &self.frames {
+            let mut sub = match sprite.sub_sprite(frame.pos, frame.size) {
+                Some(sub) => sub,
+                None => return Err(bad_sheet(&format!("frame `{}` is outside of the image",
+                                                      frame.name))),
+            };
+            sub.set_pivot(frame.sprite_pivot());
+            sheet.mapping.insert(frame.name.clone(), sub);
+        }
+        Ok(sheet)
+    }
+
+    /// Finds a frame by name.
+    pub fn frame(&self, name: &str) -> Option<&SheetFrame> {
+        self.frames.iter().find(|f| f.name == name)
+    }
+
+    /// Finds a tag by name.
+    pub fn tag(&self, name: &str) -> Option<&FrameTag> {
+        self.tags.iter().find(|t| t.name == name)
+    }
+
+    /// Returns the frames that a tag covers, in the order that they appear
+    /// in the file.
+    pub fn tag_frames(&self, tag: &FrameTag) -> &[SheetFrame] {
+        &self.frames[tag.from .. tag.to + 1]
+    }
+}
+
+/// Loads a sprite sheet from a TexturePacker or Aseprite JSON file and the
+/// image that it names.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::load_sheet_json;
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let (sheet, data) = load_sheet_json(&window, "./assets/hero.json").unwrap();
+/// let idle = sheet.get("idle 0.aseprite");
+/// let run = data.tag("run").unwrap();
+///# }
+/// ```
+pub fn load_sheet_json<D, P: ?Sized>(d: &D, path: &P)
+-> LuxResult<(NonUniformSpriteSheet<String>, SheetData)>
+where D: StateLike, P: AsRef<Path> {
+    let path = path.as_ref();
+    let mut source = String::new();
+    try!(try!(File::open(path)).read_to_string(&mut source));
+    let data = try!(SheetData::from_json(&source));
+
+    let image = match data.image {
+        Some(ref image) => path.with_file_name(image),
+        None => return Err(bad_sheet("the sheet doesn't name its image")),
+    };
+    let sprite = try!(image.as_path().into_sprite(d));
+    let sheet = try!(data.sprite_sheet(&sprite));
+    Ok((sheet, data))
+}
+
+fn bad_sheet(message: &str) -> LuxError {
+    LuxError::SerializationError(format!("Invalid sprite sheet: {}", message))
+}
+
+fn field<'a>(obj: &'a Json, key: &str) -> LuxResult<&'a Json> {
+    obj.find(key).ok_or_else(|| bad_sheet(&format!("missing `{}`", key)))
+}
+
+fn number_field(obj: &Json, key: &str) -> LuxResult<f64> {
+    try!(field(obj, key)).as_f64()
+        .ok_or_else(|| bad_sheet(&format!("`{}` must be a number", key)))
+}
+
+fn string_field(obj: &Json, key: &str) -> LuxResult<String> {
+    try!(field(obj, key)).as_string()
+        .map(String::from)
+        .ok_or_else(|| bad_sheet(&format!("`{}` must be a string", key)))
+}
+
+/// Reads `x`, `y`, `w` and `h` out of an object, treating missing
+/// positions as zero.
+fn rect_field(obj: &Json, key: &str) -> LuxResult<(u32, u32, u32, u32)> {
+    let rect = try!(field(obj, key));
+    let x = rect.find("x").and_then(Json::as_f64).unwrap_or(0.0);
+    let y = rect.find("y").and_then(Json::as_f64).unwrap_or(0.0);
+    let w = try!(number_field(rect, "w"));
+    let h = try!(number_field(rect, "h"));
+    Ok((x as u32, y as u32, w as u32, h as u32))
+}
+
+fn parse_frame(name: String, frame: &Json) -> LuxResult<SheetFrame> {
+    let (x, y, w, h) = try!(rect_field(frame, "frame"));
+    let rotated = frame.find("rotated").and_then(Json::as_boolean).unwrap_or(false);
+    let trimmed = frame.find("trimmed").and_then(Json::as_boolean).unwrap_or(false);
+
+    // The frame rectangle is the size of the upright frame, even when it is
+    // stored rotated.
+    let size = if rotated { (h, w) } else { (w, h) };
+
+    let source_offset = match frame.find("spriteSourceSize") {
+        Some(_) => {
+            let (sx, sy, _, _) = try!(rect_field(frame, "spriteSourceSize"));
+            (sx, sy)
+        }
+        None => (0, 0),
+    };
+    let source_size = match frame.find("sourceSize") {
+        Some(_) => {
+            let (_, _, sw, sh) = try!(rect_field(frame, "sourceSize"));
+            (sw, sh)
+        }
+        None => (w, h),
+    };
+
+    let pivot = match frame.find("pivot") {
+        Some(pivot) => Some((try!(number_field(pivot, "x")) as Float,
+                             try!(number_field(pivot, "y")) as Float)),
+        None => None,
+    };
+    let duration = frame.find("duration").and_then(Json::as_f64).map(|d| d as u32);
+
+    Ok(SheetFrame {
+        name: name,
+        pos: (x, y),
+        size: size,
+        rotated: rotated,
+        trimmed: trimmed,
+        source_offset: source_offset,
+        source_size: source_size,
+        pivot: pivot,
+        duration: duration,
+    })
+}
+
+/// Returns the keys of the top level `frames` object in the order that they
+/// are written in.
+fn frame_order(source: &str) -> Vec<String> {
+    use rustc_serialize::json::{Parser, JsonEvent, StackElement};
+
+    let mut parser = Parser::new(source.chars());
+    let mut names = vec![];
+    while let Some(event) = parser.next() {
+        match event {
+            JsonEvent::ObjectStart => {
+                let stack = parser.stack();
+                if stack.len() == 2 && stack.starts_with(&[StackElement::Key("frames")]) {
+                    if let StackElement::Key(name) = stack.get(1) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            JsonEvent::Error(_) => break,
+            _ => {}
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SheetData, TagDirection};
+    use super::super::types::Float;
+
+    fn assert_pivot(pivot: Option<(Float, Float)>, expected: (Float, Float)) {
+        let (x, y) = pivot.expect("the frame should have a pivot");
+        assert!((x - expected.0).abs() < 1e-5 && (y - expected.1).abs() < 1e-5,
+                "{:?} != {:?}", (x, y), expected);
+    }
+
+    const HASH: &'static str = r#"{
+        "frames": {
+            "walk 2.png": { "frame": {"x": 0, "y": 0, "w": 8, "h": 8}, "duration": 100 },
+            "walk 0.png": { "frame": {"x": 8, "y": 0, "w": 8, "h": 8}, "duration": 150 },
+            "walk 1.png": { "frame": {"x": 16, "y": 0, "w": 8, "h": 8} }
+        },
+        "meta": {
+            "image": "walk.png",
+            "frameTags": [
+                { "name": "all", "from": 0, "to": 2, "direction": "pingpong" },
+                { "name": "end", "from": 1, "to": 2 }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn hash_frames_keep_the_order_of_the_file() {
+        let data = SheetData::from_json(HASH).unwrap();
+        let names: Vec<_> = data.frames.iter().map(|f| &f.name[..]).collect();
+        assert_eq!(names, vec!["walk 2.png", "walk 0.png", "walk 1.png"]);
+        assert_eq!(data.image, Some("walk.png".to_string()));
+        assert_eq!(data.frames[1].pos, (8, 0));
+        assert_eq!(data.frames[1].duration, Some(150));
+        assert_eq!(data.frames[2].duration, None);
+    }
+
+    #[test]
+    fn tags_refer_to_frames_by_position() {
+        let data = SheetData::from_json(HASH).unwrap();
+        let all = data.tag("all").unwrap();
+        assert_eq!(all.direction, TagDirection::PingPong);
+        let end = data.tag("end").unwrap();
+        assert_eq!(end.direction, TagDirection::Forward);
+        let names: Vec<_> = data.tag_frames(end).iter().map(|f| &f.name[..]).collect();
+        assert_eq!(names, vec!["walk 0.png", "walk 1.png"]);
+    }
+
+    #[test]
+    fn tags_outside_of_the_frames_are_errors() {
+        let source = r#"{
+            "frames": [ { "filename": "a", "frame": {"x": 0, "y": 0, "w": 1, "h": 1} } ],
+            "meta": { "frameTags": [ { "name": "bad", "from": 0, "to": 1 } ] }
+        }"#;
+        assert!(SheetData::from_json(source).is_err());
+        assert!(SheetData::from_json(r#"{ "frames": 5 }"#).is_err());
+        assert!(SheetData::from_json(r#"{ "frames": [ { "frame": {"w": 1, "h": 1} } ] }"#).is_err());
+    }
+
+    const ARRAY: &'static str = r#"{
+        "frames": [
+            {
+                "filename": "upright",
+                "frame": {"x": 0, "y": 0, "w": 10, "h": 20},
+                "rotated": false,
+                "trimmed": true,
+                "spriteSourceSize": {"x": 5, "y": 10, "w": 10, "h": 20},
+                "sourceSize": {"w": 20, "h": 40},
+                "pivot": {"x": 0.5, "y": 0.5}
+            },
+            {
+                "filename": "rotated",
+                "frame": {"x": 10, "y": 0, "w": 10, "h": 20},
+                "rotated": true,
+                "trimmed": false,
+                "pivot": {"x": 0.0, "y": 0.0}
+            },
+            {
+                "filename": "plain",
+                "frame": {"x": 0, "y": 20, "w": 4, "h": 4}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn array_frames_are_read_in_order() {
+        let data = SheetData::from_json(ARRAY).unwrap();
+        let names: Vec<_> = data.frames.iter().map(|f| &f.name[..]).collect();
+        assert_eq!(names, vec!["upright", "rotated", "plain"]);
+        assert!(data.image.is_none());
+        assert!(data.tags.is_empty());
+    }
+
+    #[test]
+    fn rotated_frames_are_stored_sideways() {
+        let data = SheetData::from_json(ARRAY).unwrap();
+        let frame = data.frame("rotated").unwrap();
+        assert_eq!(frame.size, (20, 10));
+        assert_eq!(frame.upright_size(), (10, 20));
+        assert!(frame.rotation() != 0.0);
+        assert_eq!(data.frame("plain").unwrap().rotation(), 0.0);
+    }
+
+    #[test]
+    fn trimmed_pivots_point_at_the_original_image() {
+        let data = SheetData::from_json(ARRAY).unwrap();
+        // The center of the 20x40 source is at (10, 20), which is (5, 10)
+        // into the trimmed frame.
+        assert_pivot(data.frame("upright").unwrap().sprite_pivot(), (0.5, 0.5));
+        assert!(data.frame("plain").unwrap().sprite_pivot().is_none());
+    }
+
+    #[test]
+    fn rotated_pivots_follow_the_frame() {
+        let data = SheetData::from_json(ARRAY).unwrap();
+        // The top left of the upright frame is the top right once it is
+        // rotated clockwise onto the sheet.
+        assert_pivot(data.frame("rotated").unwrap().sprite_pivot(), (1.0, 0.0));
+    }
+}
@@ -17,7 +17,7 @@ use super::accessors::{Fetch, DrawLike, StateLike, StateFields, DrawFields, Draw
 
 use super::error::{LuxError, LuxResult};
 use super::color::rgb;
-use super::gfx_integration::{TexVertex, ColorVertex};
+use super::gfx_integration::{TexVertex, ColorVertex, Sampler};
 use super::canvas::{Canvas, Rectangle};
 use super::raw::Transform;
 use super::primitive_canvas::{CachedColorDraw, CachedTexDraw, DrawParamModifier};
@@ -25,6 +25,7 @@ use super::primitive_canvas::{CachedColorDraw, CachedTexDraw, DrawParamModifier}
 use vecmath;
 use poison_pool;
 use font_atlas::cache::FontCache;
+use super::font::BakedFonts;
 
 /// An owned texture on the hardware.
 pub struct Texture {
@@ -45,6 +46,9 @@ pub struct Sprite {
 
     texture_size: (Float, Float),
     texture_pos: (Float, Float),
+
+    sampler: Sampler,
+    pivot: Option<(Float, Float)>,
 }
 
 // TODO: add font rendering
@@ -57,10 +61,12 @@ pub struct DrawableTexture<'a, D: 'a + StateLike> {
     d: &'a D,
 
     matrix: [[Float; 4]; 4],
+    base_matrix: [[Float; 4]; 4],
 
     color_draw_cache: Option<CachedColorDraw>,
     tex_draw_cache: Option<CachedTexDraw>,
     font_cache: Rc<RefCell<FontCache<Sprite>>>,
+    baked_fonts: Rc<RefCell<BakedFonts>>,
     draw_mod: DrawParamModifier
 }
 
@@ -207,9 +213,11 @@ impl <'a, D: StateLike> DrawableTexture<'a, D> {
             texture: texture,
             d: d,
             matrix: basis,
+            base_matrix: basis,
             color_draw_cache: None,
             tex_draw_cache: None,
             font_cache: d.state_fields().font_cache.clone(),
+            baked_fonts: d.state_fields().baked_fonts.clone(),
             draw_mod: DrawParamModifier::new()
         }
     }
@@ -222,6 +230,9 @@ impl <'a, D: StateLike> Transform for DrawableTexture<'a, D> {
     fn current_matrix_mut(&mut self) -> &mut [[Float; 4]; 4] {
         &mut self.matrix
     }
+    fn base_matrix(&self) -> [[Float; 4]; 4] {
+        self.base_matrix
+    }
 }
 
 impl <'a, D: StateLike> Fetch<Vec<Idx>> for DrawableTexture<'a, D> {
@@ -258,7 +269,9 @@ impl <'a, D: StateLike> DrawLike for DrawableTexture<'a, D> {
             display: display,
             scissor: &mut self.draw_mod.scissor,
             stencil_state: &mut self.draw_mod.stencil_state,
+            state_stack: &mut self.draw_mod.state_stack,
             font_cache: &mut self.font_cache,
+            baked_fonts: &mut self.baked_fonts,
             texture_shader: texture_shader,
             color_shader: color_shader,
             color_draw_cache: &mut self.color_draw_cache,
@@ -275,7 +288,9 @@ impl <'a, D: StateLike> DrawLike for DrawableTexture<'a, D> {
             display: display,
             scissor: &self.draw_mod.scissor,
             stencil_state: &self.draw_mod.stencil_state,
+            state_stack: &self.draw_mod.state_stack,
             font_cache: &self.font_cache,
+            baked_fonts: &self.baked_fonts,
             texture_shader: texture_shader,
             color_shader: color_shader,
             color_draw_cache: &self.color_draw_cache,
@@ -301,6 +316,12 @@ impl <'a, D: StateLike> Drop for DrawableTexture<'a, D> {
     }
 }
 
+/// Wraps a texture that was created elsewhere in the crate in a sprite that
+/// covers all of it.
+pub fn sprite_from_texture(tex: Rc<glium::texture::Texture2d>) -> Sprite {
+    Sprite::new(tex)
+}
+
 impl Sprite {
     fn new(tex: Rc<glium::texture::Texture2d>) -> Sprite {
         use glium::Surface;
@@ -312,10 +333,58 @@ impl Sprite {
             pos: (0, 0),
 
             texture_size: (1.0, 1.0),
-            texture_pos: (0.0, 0.0)
+            texture_pos: (0.0, 0.0),
+
+            sampler: Sampler::default(),
+            pivot: None,
         }
     }
 
+    /// Returns the settings that are used to sample this sprite's texture.
+    pub fn sampler(&self) -> Sampler {
+        self.sampler
+    }
+
+    /// Sets the settings that are used to sample this sprite's texture.
+    pub fn set_sampler(&mut self, sampler: Sampler) {
+        self.sampler = sampler;
+    }
+
+    /// Returns a copy of this sprite that is sampled with different settings.
+    ///
+    /// ```rust,no_run
+    ///# extern crate lux;
+    ///# use lux::prelude::*;
+    ///# use lux::graphics::Sampler;
+    ///# fn main() {
+    ///# let mut window = Window::new_with_defaults().unwrap();
+    /// let sprite = window.load_texture_file("./test/test.png").unwrap().into_sprite();
+    /// let pixelated = sprite.with_sampler(Sampler::nearest());
+    ///# }
+    /// ```
+    pub fn with_sampler(&self, sampler: Sampler) -> Sprite {
+        Sprite { sampler: sampler, .. self.clone() }
+    }
+
+    /// Returns the point that pictures of this sprite are positioned,
+    /// rotated and scaled around when they don't set an origin of their own.
+    ///
+    /// The pivot is relative to the size of the sprite, so `(0.5, 0.5)` is
+    /// the center.  `None` means the top left corner.
+    pub fn pivot(&self) -> Option<(Float, Float)> {
+        self.pivot
+    }
+
+    /// Sets the default pivot of this sprite.
+    pub fn set_pivot(&mut self, pivot: Option<(Float, Float)>) {
+        self.pivot = pivot;
+    }
+
+    /// Returns a copy of this sprite with a different default pivot.
+    pub fn with_pivot(&self, pivot: (Float, Float)) -> Sprite {
+        Sprite { pivot: Some(pivot), .. self.clone() }
+    }
+
     /// Returns the size of this sprite.
     pub fn size(&self) -> (f32, f32) {
         (self.size.0 as f32, self.size.1 as f32)
@@ -355,10 +424,19 @@ impl Sprite {
             texture_size: (size.0 as Float / self.original_size.0 as Float,
                            size.1 as Float / self.original_size.1 as Float),
             texture_pos: (pos.0 as Float / self.original_size.0 as Float,
-                          pos.1 as Float / self.original_size.1 as Float)
+                          pos.1 as Float / self.original_size.1 as Float),
+
+            sampler: self.sampler,
+            pivot: self.pivot,
         })
     }
 
+    /// Returns true if this sprite covers the entire texture that it was
+    /// loaded from.
+    pub fn is_whole_texture(&self) -> bool {
+        self.pos == (0, 0) && self.size == self.original_size
+    }
+
     /// Returns a sprite that contains the entire texture that the sprite
     /// was loaded from.
     pub fn original_sprite(&self) -> Sprite {
@@ -368,7 +446,9 @@ impl Sprite {
             size: self.original_size,
             pos: (0, 0),
             texture_size: (1.0, 1.0),
-            texture_pos: (0.0, 0.0)
+            texture_pos: (0.0, 0.0),
+            sampler: self.sampler,
+            pivot: self.pivot,
         }
     }
 
@@ -0,0 +1,823 @@
+//! CPU-side tessellation of outlines and filled areas into triangles that
+//! can be batched through `draw_colored`.
+
+use std::f32::consts::PI;
+
+use super::gfx_integration::ColorVertex;
+use super::types::{Float, Idx};
+
+type Point = (Float, Float);
+
+/// Computes a unit normal for the segment that goes from `from` to `to`.
+fn normal(from: Point, to: Point) -> Point {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 { (0.0, 0.0) } else { (-dy / len, dx / len) }
+}
+
+/// The distance between two points.
+fn dist(a: Point, b: Point) -> Float {
+    ((b.0 - a.0) * (b.0 - a.0) + (b.1 - a.1) * (b.1 - a.1)).sqrt()
+}
+
+/// Removes points that are at the same position as the point before them.
+fn dedup(points: &[Point]) -> Vec<Point> {
+    let mut out: Vec<Point> = Vec::with_capacity(points.len());
+    for &p in points {
+        if out.last().map(|&l| l != p).unwrap_or(true) {
+            out.push(p);
+        }
+    }
+    out
+}
+
+/// How two connected segments of a stroke are joined together.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet in a point.
+    Miter,
+    /// The corner is rounded off with a circular arc.
+    Round,
+    /// The corner is cut off with a straight line.
+    Bevel,
+}
+
+/// How the ends of an open stroke are drawn.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineCap {
+    /// The stroke stops exactly at the end point.
+    Butt,
+    /// The stroke extends past the end point by half of its width.
+    Square,
+    /// The stroke ends in a half circle around the end point.
+    Round,
+}
+
+/// Everything besides color that describes the shape of a stroke.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StrokeStyle {
+    /// The width of the stroke.
+    pub width: Float,
+    /// How corners are drawn.
+    pub join: LineJoin,
+    /// How the ends of open strokes are drawn.
+    pub cap: LineCap,
+    /// The longest that a miter may be, as a multiple of the stroke width,
+    /// before the join falls back to a bevel.
+    pub miter_limit: Float,
+    /// The pattern of dashes to break the stroke into, if any.
+    pub dash: Option<Dash>,
+}
+
+impl StrokeStyle {
+    /// A mitered stroke with butt caps.
+    pub fn new(width: Float) -> StrokeStyle {
+        StrokeStyle {
+            width: width,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash: None,
+        }
+    }
+}
+
+/// The most lengths that a `Dash` pattern can hold.
+pub const MAX_DASH_LENGTHS: usize = 8;
+
+/// The most times that a dash pattern repeats along one line.  Patterns
+/// that are tiny next to the line are stretched to fit.
+const MAX_DASH_PERIODS: Float = 4096.0;
+
+/// A repeating pattern of dashes and gaps along a stroke.
+///
+/// Lengths are measured along the shape before any transformation is
+/// applied, so the pattern is scaled and rotated along with the shape and
+/// stays continuous around corners.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Dash {
+    lengths: [Float; MAX_DASH_LENGTHS],
+    count: usize,
+    /// How far into the pattern the stroke starts.  Changing this every
+    /// frame makes the dashes march along the stroke.
+    pub offset: Float,
+}
+
+impl Dash {
+    /// Creates a pattern that alternates between dashes and gaps of the
+    /// given lengths, starting with a dash.
+    ///
+    /// A list with an odd number of lengths is repeated, so `[5.0]` is the
+    /// same as `[5.0, 5.0]`.  Dashes with a length of zero are drawn as dots
+    /// when the stroke has round or square caps.  Only the first
+    /// `MAX_DASH_LENGTHS` lengths are used.
+    pub fn new(lengths: &[Float], offset: Float) -> Dash {
+        let mut dash = Dash {
+            lengths: [0.0; MAX_DASH_LENGTHS],
+            count: lengths.len().min(MAX_DASH_LENGTHS),
+            offset: offset,
+        };
+        for (to, &from) in dash.lengths.iter_mut().zip(lengths.iter()) {
+            *to = from.max(0.0);
+        }
+        dash
+    }
+
+    /// Returns the lengths that make up this pattern.
+    pub fn lengths(&self) -> &[Float] {
+        &self.lengths[.. self.count]
+    }
+
+    /// Returns the same pattern starting at a different offset.
+    pub fn with_offset(&self, offset: Float) -> Dash {
+        Dash { offset: offset, .. *self }
+    }
+
+    /// Returns true if this pattern would draw a solid line.
+    fn is_solid(&self) -> bool {
+        self.lengths().iter().fold(0.0, |a, &b| a + b) <= 0.0
+    }
+
+    /// Splits a line through `points` into the pieces that are covered by
+    /// dashes.
+    pub fn split(&self, points: &[Point], closed: bool) -> Vec<Vec<Point>> {
+        let mut points = dedup(points);
+        if closed && points.len() > 1 {
+            let first = points[0];
+            if points.last() != Some(&first) {
+                points.push(first);
+            }
+        }
+        if points.len() < 2 {
+            return vec![];
+        }
+        if self.is_solid() {
+            return vec![points];
+        }
+
+        // Stretch patterns that would repeat too often, and make every
+        // length that isn't zero long enough to move along the line.
+        // Otherwise tiny dashes would make an endless number of pieces.
+        let total = points.windows(2).fold(0.0, |a, s| {
+            a + ((s[1].0 - s[0].0).powi(2) + (s[1].1 - s[0].1).powi(2)).sqrt()
+        });
+        let sum = self.lengths().iter().fold(0.0, |a, &b| a + b);
+        let scale = (total / (MAX_DASH_PERIODS * sum)).max(1.0);
+        let min_length = total * 1e-5;
+        let lengths: Vec<Float> = self.lengths().iter().map(|&l| {
+            if l > 0.0 { (l * scale).max(min_length) } else { 0.0 }
+        }).collect();
+        let lengths = &lengths[..];
+
+        // An odd pattern has to be repeated for the dashes and gaps to line
+        // up again.
+        let sum = lengths.iter().fold(0.0, |a, &b| a + b);
+        let period = if lengths.len() % 2 == 0 { sum } else { sum * 2.0 };
+        let mut phase = (self.offset * scale) % period;
+        if phase < 0.0 {
+            phase += period;
+        }
+
+        let mut index = 0;
+        let mut on = true;
+        let mut remaining = lengths[0];
+        while phase > remaining {
+            phase -= remaining;
+            index = (index + 1) % lengths.len();
+            on = !on;
+            remaining = lengths[index];
+        }
+        remaining -= phase;
+
+        // Zero length dashes are given a tiny length in the direction of
+        // the line so that their caps are still drawn.
+        const DOT: Float = 1e-3;
+
+        let starts_on = on;
+        let mut pieces = vec![];
+        let mut piece = if on { vec![points[0]] } else { vec![] };
+        let (mut ux, mut uy) = (0.0, 0.0);
+        for segment in points.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let len = (dx * dx + dy * dy).sqrt();
+            ux = dx / len;
+            uy = dy / len;
+
+            let mut t = 0.0;
+            while len - t > remaining {
+                t += remaining;
+                let p = (a.0 + ux * t, a.1 + uy * t);
+                if on {
+                    if lengths[index] == 0.0 {
+                        piece.push((p.0 + ux * DOT, p.1 + uy * DOT));
+                    } else if piece.last() != Some(&p) {
+                        piece.push(p);
+                    }
+                    if piece.len() > 1 {
+                        pieces.push(piece);
+                    }
+                    piece = vec![];
+                } else {
+                    piece.push(p);
+                }
+                index = (index + 1) % lengths.len();
+                on = !on;
+                remaining = lengths[index];
+            }
+            remaining -= len - t;
+            if on && piece.last() != Some(&b) {
+                piece.push(b);
+            }
+        }
+
+        if on && piece.len() == 1 && lengths[index] == 0.0 {
+            let p = piece[0];
+            piece.push((p.0 + ux * DOT, p.1 + uy * DOT));
+        }
+        if on && piece.len() > 1 {
+            // The dash that runs over the start of a closed line continues
+            // into the first dash.
+            if closed && starts_on && !pieces.is_empty() {
+                let first = pieces.remove(0);
+                piece.extend_from_slice(&first[1 ..]);
+            }
+            pieces.push(piece);
+        }
+        pieces
+    }
+}
+
+/// How far the segments of round joins and caps may stray from a true
+/// circle.
+const ROUND_TOLERANCE: Float = 0.1;
+
+/// Appends the points of a circular arc around `center`, not including
+/// the point at `start_angle`.
+pub fn arc_points(center: Point, radius: Float, start_angle: Float, sweep: Float,
+                  tolerance: Float, out: &mut Vec<Point>) {
+    let max_step = if radius > tolerance {
+        2.0 * (1.0 - tolerance / radius).acos()
+    } else {
+        PI / 2.0
+    };
+    let n = ((sweep.abs() / max_step.max(0.001)).ceil() as u32).max(1).min(1024);
+    for i in 1 .. n + 1 {
+        let theta = start_angle + sweep * (i as Float / n as Float);
+        out.push((center.0 + radius * theta.cos(), center.1 + radius * theta.sin()));
+    }
+}
+
+fn push_vertex(p: Point, color: [f32; 4], vertices: &mut Vec<ColorVertex>) -> Idx {
+    vertices.push(ColorVertex { pos: [p.0, p.1], color: color });
+    (vertices.len() - 1) as Idx
+}
+
+/// Fills the area between `pivot` and an arc around `center` that starts
+/// at the vertex `from` and ends at the vertex `to`.
+fn round_fan(pivot: Idx, center: Point, radius: Float, start_angle: Float, sweep: Float,
+             from: Idx, to: Idx, color: [f32; 4],
+             vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+    let mut arc = vec![];
+    arc_points(center, radius, start_angle, sweep, ROUND_TOLERANCE, &mut arc);
+    arc.pop();
+
+    let mut last = from;
+    for p in arc {
+        let next = push_vertex(p, color, vertices);
+        idxs.extend_from_slice(&[pivot, last, next]);
+        last = next;
+    }
+    idxs.extend_from_slice(&[pivot, last, to]);
+}
+
+/// Builds the end of an open stroke at `cur`, where `d` is the direction
+/// of the segment and `n` is its normal.  Returns the left and right
+/// vertices.
+fn stroke_cap(cur: Point, d: Point, n: Point, at_start: bool, half: Float, cap: LineCap,
+              color: [f32; 4], vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) -> [Idx; 2] {
+    let outward = if at_start { -1.0 } else { 1.0 };
+    let shift = if cap == LineCap::Square { half * outward } else { 0.0 };
+    let (cx, cy) = (cur.0 + d.0 * shift, cur.1 + d.1 * shift);
+
+    let left = push_vertex((cx + n.0 * half, cy + n.1 * half), color, vertices);
+    let right = push_vertex((cx - n.0 * half, cy - n.1 * half), color, vertices);
+
+    if cap == LineCap::Round {
+        let pivot = push_vertex(cur, color, vertices);
+        if at_start {
+            round_fan(pivot, cur, half, n.1.atan2(n.0), PI, left, right, color, vertices, idxs);
+        } else {
+            round_fan(pivot, cur, half, (-n.1).atan2(-n.0), PI, right, left, color, vertices, idxs);
+        }
+    }
+
+    [left, right]
+}
+
+/// Builds the corner at `cur` between the segment coming from `prev` and
+/// the segment going to `next`.  Returns the left and right vertices of the
+/// incoming segment followed by those of the outgoing segment.
+fn stroke_join(prev: Point, cur: Point, next: Point, half: Float, style: &StrokeStyle,
+               color: [f32; 4], vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>)
+               -> ([Idx; 2], [Idx; 2]) {
+    let n0 = normal(prev, cur);
+    let n1 = normal(cur, next);
+    let (d0, d1) = ((n0.1, -n0.0), (n1.1, -n1.0));
+
+    let (mx, my) = (n0.0 + n1.0, n0.1 + n1.1);
+    let m_len = (mx * mx + my * my).sqrt();
+    let (m, cos) = if m_len < 1e-6 {
+        ((0.0, 0.0), 0.0)
+    } else {
+        let m = (mx / m_len, my / m_len);
+        (m, m.0 * n1.0 + m.1 * n1.1)
+    };
+
+    // Straight through, so there is no corner to fill.
+    if cos > 0.9999 {
+        let left = push_vertex((cur.0 + n1.0 * half, cur.1 + n1.1 * half), color, vertices);
+        let right = push_vertex((cur.0 - n1.0 * half, cur.1 - n1.1 * half), color, vertices);
+        return ([left, right], [left, right]);
+    }
+
+    // The side that the corner points away from; 1.0 for the left.
+    let cross = d0.0 * d1.1 - d0.1 * d1.0;
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+
+    let outer_in = push_vertex((cur.0 + side * n0.0 * half, cur.1 + side * n0.1 * half), color, vertices);
+    let outer_out = push_vertex((cur.0 + side * n1.0 * half, cur.1 + side * n1.1 * half), color, vertices);
+
+    // The inner edges are clipped where they cross, so that neither
+    // segment covers the other.  That point is only on both segments if
+    // they are long enough; otherwise the segments end square at `cur`
+    // and overlap on the inside of the corner.
+    let clip = if cos > 0.0 { half / cos } else { 0.0 };
+    let along = clip * (m.0 * d0.0 + m.1 * d0.1).abs();
+    let shortest = dist(prev, cur).min(dist(cur, next));
+    let (inner_in, inner_out, pivot) = if cos > 0.0 && along <= 0.5 * shortest {
+        let inner = push_vertex((cur.0 - side * m.0 * clip, cur.1 - side * m.1 * clip), color, vertices);
+        (inner, inner, inner)
+    } else {
+        let inner_in = push_vertex((cur.0 - side * n0.0 * half, cur.1 - side * n0.1 * half), color, vertices);
+        let inner_out = push_vertex((cur.0 - side * n1.0 * half, cur.1 - side * n1.1 * half), color, vertices);
+        (inner_in, inner_out, push_vertex(cur, color, vertices))
+    };
+
+    let miter_fits = cos > 0.0 && 1.0 / cos <= style.miter_limit;
+    match style.join {
+        LineJoin::Miter if miter_fits => {
+            let scale = half / cos;
+            let tip = push_vertex((cur.0 + side * m.0 * scale, cur.1 + side * m.1 * scale), color, vertices);
+            idxs.extend_from_slice(&[pivot, outer_in, tip, pivot, tip, outer_out]);
+        }
+        LineJoin::Round => {
+            let start_angle = (side * n0.1).atan2(side * n0.0);
+            let end_angle = (side * n1.1).atan2(side * n1.0);
+            let mut sweep = end_angle - start_angle;
+            if m_len < 1e-6 {
+                sweep = -side * PI;
+            } else {
+                while sweep > PI { sweep -= 2.0 * PI; }
+                while sweep < -PI { sweep += 2.0 * PI; }
+            }
+            round_fan(pivot, cur, half, start_angle, sweep, outer_in, outer_out, color, vertices, idxs);
+        }
+        _ => {
+            idxs.extend_from_slice(&[pivot, outer_in, outer_out]);
+        }
+    }
+
+    if side > 0.0 {
+        ([outer_in, inner_in], [outer_out, inner_out])
+    } else {
+        ([inner_in, outer_in], [inner_out, outer_out])
+    }
+}
+
+/// Builds the triangles for a stroke that follows a list of points.
+///
+/// The stroke is centered on the line between points.  Corners are built
+/// according to the join style of `style` and, unless `closed` is true,
+/// the ends are built according to its cap style.
+///
+/// Triangles can overlap where the line crosses itself, and on the inside
+/// of sharp corners between segments that are shorter than the stroke is
+/// wide.  Translucent strokes are blended twice in those places.
+pub fn stroke_polyline(points: &[Point], closed: bool, style: &StrokeStyle, color: [f32; 4],
+                       vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+    let mut points = dedup(points);
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let count = points.len();
+    if count < 2 || style.width <= 0.0 {
+        return;
+    }
+
+    if let Some(dash) = style.dash.and_then(|d| if d.is_solid() { None } else { Some(d) }) {
+        let solid = StrokeStyle { dash: None, .. *style };
+        for piece in dash.split(&points[..], closed) {
+            stroke_polyline(&piece[..], false, &solid, color, vertices, idxs);
+        }
+        return;
+    }
+
+    let half = style.width / 2.0;
+    let mut ends = Vec::with_capacity(count);
+    for i in 0 .. count {
+        let cur = points[i];
+        let prev = if i > 0 { Some(points[i - 1]) }
+                   else if closed { Some(points[count - 1]) }
+                   else { None };
+        let next = if i + 1 < count { Some(points[i + 1]) }
+                   else if closed { Some(points[0]) }
+                   else { None };
+
+        ends.push(match (prev, next) {
+            (Some(prev), Some(next)) => {
+                stroke_join(prev, cur, next, half, style, color, vertices, idxs)
+            }
+            (None, Some(next)) => {
+                let n = normal(cur, next);
+                let end = stroke_cap(cur, (n.1, -n.0), n, true, half, style.cap, color, vertices, idxs);
+                (end, end)
+            }
+            (Some(prev), None) => {
+                let n = normal(prev, cur);
+                let end = stroke_cap(cur, (n.1, -n.0), n, false, half, style.cap, color, vertices, idxs);
+                (end, end)
+            }
+            (None, None) => unreachable!(),
+        });
+    }
+
+    let segments = if closed { count } else { count - 1 };
+    for i in 0 .. segments {
+        let a = ends[i].1;
+        let b = ends[(i + 1) % count].0;
+        idxs.extend_from_slice(&[a[0], a[1], b[0], b[0], a[1], b[1]]);
+    }
+}
+
+/// Fills a convex polygon with a single color.
+pub fn fill_convex(points: &[Point], color: [f32; 4],
+                   vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+    let base = vertices.len() as Idx;
+    vertices.extend(points.iter().map(|&(x, y)| ColorVertex { pos: [x, y], color: color }));
+    for i in 1 .. (points.len() as Idx).saturating_sub(1) {
+        idxs.extend_from_slice(&[base, base + i, base + i + 1]);
+    }
+}
+
+/// Decides which areas of overlapping or self-intersecting contours are
+/// considered inside of a shape.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FillRule {
+    /// A point is inside if the contours wind around it a non-zero number of
+    /// times.  Contours that run in opposite directions cut holes in each
+    /// other.
+    NonZero,
+    /// A point is inside if a ray from it crosses an odd number of edges.
+    /// Nested contours cut holes regardless of their direction.
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(&self, winding: i32) -> bool {
+        match *self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Returns twice the signed area of a contour.  The sign tells which way
+/// the contour winds.
+pub fn signed_area(points: &[Point]) -> Float {
+    let count = points.len();
+    let mut area = 0.0;
+    for i in 0 .. count {
+        let (a, b) = (points[i], points[(i + 1) % count]);
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    area
+}
+
+/// An edge of a polygon, stored so that `y0 < y1`.
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    x0: Float,
+    y0: Float,
+    x1: Float,
+    y1: Float,
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: Float) -> Float {
+        let t = (y - self.y0) / (self.y1 - self.y0);
+        self.x0 + (self.x1 - self.x0) * t
+    }
+
+    /// Returns the y coordinate where two edges cross, if they do.
+    fn intersection_y(&self, other: &Edge) -> Option<Float> {
+        let (ax, ay) = (self.x1 - self.x0, self.y1 - self.y0);
+        let (bx, by) = (other.x1 - other.x0, other.y1 - other.y0);
+        let denom = ax * by - ay * bx;
+        if denom.abs() < 1e-9 {
+            return None;
+        }
+
+        let (cx, cy) = (other.x0 - self.x0, other.y0 - self.y0);
+        let t = (cx * by - cy * bx) / denom;
+        let u = (cx * ay - cy * ax) / denom;
+        if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+            Some(self.y0 + ay * t)
+        } else {
+            None
+        }
+    }
+}
+
+/// Fills any set of closed contours using the given fill rule.
+///
+/// The contours may be concave, may intersect themselves and each other,
+/// and may be nested.  The area is cut into horizontal bands at every vertex
+/// and every crossing, so within a band no two edges cross and the covered
+/// spans can be emitted as trapezoids.
+///
+/// Points that aren't finite are skipped.  Finding the crossings checks
+/// every pair of edges, so this is meant for icons and UI shapes rather
+/// than for contours with thousands of points.
+pub fn fill_contours(contours: &[Vec<Point>], rule: FillRule, color: [f32; 4],
+                     vertices: &mut Vec<ColorVertex>, idxs: &mut Vec<Idx>) {
+    const EPSILON: Float = 1e-4;
+
+    let mut edges = vec![];
+    for contour in contours {
+        let finite: Vec<Point> = contour.iter()
+                                        .cloned()
+                                        .filter(|p| p.0.is_finite() && p.1.is_finite())
+                                        .collect();
+        let contour = dedup(&finite[..]);
+        let count = contour.len();
+        if count < 3 {
+            continue;
+        }
+        for i in 0 .. count {
+            let (a, b) = (contour[i], contour[(i + 1) % count]);
+            if a.1 < b.1 {
+                edges.push(Edge { x0: a.0, y0: a.1, x1: b.0, y1: b.1, winding: 1 });
+            } else if a.1 > b.1 {
+                edges.push(Edge { x0: b.0, y0: b.1, x1: a.0, y1: a.1, winding: -1 });
+            }
+        }
+    }
+
+    let mut ys: Vec<Float> = vec![];
+    for (i, edge) in edges.iter().enumerate() {
+        ys.push(edge.y0);
+        ys.push(edge.y1);
+        for other in &edges[i + 1 ..] {
+            if let Some(y) = edge.intersection_y(other) {
+                ys.push(y);
+            }
+        }
+    }
+    // Crossings of nearly parallel edges can still overflow.
+    ys.retain(|y| y.is_finite());
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut active: Vec<(Float, Float, Float, i32)> = vec![];
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+        if bottom - top < EPSILON {
+            continue;
+        }
+        let mid = (top + bottom) / 2.0;
+
+        active.clear();
+        active.extend(edges.iter()
+                           .filter(|e| e.y0 < mid && e.y1 > mid)
+                           .map(|e| (e.x_at(mid), e.x_at(top), e.x_at(bottom), e.winding)));
+        active.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut winding = 0;
+        let mut left = None;
+        for &(_, x_top, x_bottom, dir) in &active {
+            let was_inside = rule.is_inside(winding);
+            winding += dir;
+            let is_inside = rule.is_inside(winding);
+
+            if !was_inside && is_inside {
+                left = Some((x_top, x_bottom));
+            } else if was_inside && !is_inside {
+                if let Some((l_top, l_bottom)) = left.take() {
+                    let base = vertices.len() as Idx;
+                    vertices.push(ColorVertex { pos: [l_top, top], color: color });
+                    vertices.push(ColorVertex { pos: [x_top, top], color: color });
+                    vertices.push(ColorVertex { pos: [x_bottom, bottom], color: color });
+                    vertices.push(ColorVertex { pos: [l_bottom, bottom], color: color });
+                    idxs.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_contours, signed_area, stroke_polyline, Dash, FillRule, LineJoin, Point,
+                StrokeStyle, MAX_DASH_PERIODS};
+    use super::super::gfx_integration::ColorVertex;
+    use super::super::types::{Float, Idx};
+
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    fn square(x: Float, y: Float, size: Float) -> Vec<Point> {
+        vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+    }
+
+    /// Fills the contours and adds up the area of every triangle.
+    fn filled_area(contours: &[Vec<Point>], rule: FillRule) -> Float {
+        let (mut vertices, mut idxs): (Vec<ColorVertex>, Vec<Idx>) = (vec![], vec![]);
+        fill_contours(contours, rule, BLACK, &mut vertices, &mut idxs);
+        idxs.chunks(3).map(|t| {
+            let (a, b, c) = (vertices[t[0] as usize].pos,
+                             vertices[t[1] as usize].pos,
+                             vertices[t[2] as usize].pos);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.0
+        }).fold(0.0, |a, b| a + b)
+    }
+
+    /// Returns how many triangles cover the point, leaving out those that
+    /// only touch it with an edge.
+    fn coverage(vertices: &[ColorVertex], idxs: &[Idx], p: Point) -> usize {
+        idxs.chunks(3).filter(|t| {
+            let (a, b, c) = (vertices[t[0] as usize].pos,
+                             vertices[t[1] as usize].pos,
+                             vertices[t[2] as usize].pos);
+            let area = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+            let edge = |u: [f32; 2], v: [f32; 2]| {
+                ((v[0] - u[0]) * (p.1 - u[1]) - (p.0 - u[0]) * (v[1] - u[1])) / area
+            };
+            area.abs() > 1e-6 && edge(a, b) > 1e-4 && edge(b, c) > 1e-4 && edge(c, a) > 1e-4
+        }).count()
+    }
+
+    fn assert_close(a: Float, b: Float) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn acute_corners_do_not_overlap() {
+        let lines = [vec![(0.0, 0.0), (400.0, 0.0), (0.0, 20.0)],
+                     vec![(0.0, 0.0), (100.0, 0.0), (20.0, 30.0)]];
+        for line in &lines {
+            for &join in &[LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+                let style = StrokeStyle { join: join, miter_limit: 100.0, .. StrokeStyle::new(4.0) };
+                let (mut vertices, mut idxs): (Vec<ColorVertex>, Vec<Idx>) = (vec![], vec![]);
+                stroke_polyline(&line[..], false, &style, BLACK, &mut vertices, &mut idxs);
+
+                let mut y = -5.0;
+                while y < 35.0 {
+                    let mut x = -5.0;
+                    while x < 405.0 {
+                        let covered = coverage(&vertices, &idxs, (x, y));
+                        assert!(covered <= 1, "{:?} covered {} times with {:?}", (x, y), covered, join);
+                        x += 0.37;
+                    }
+                    y += 0.37;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn signed_area_follows_winding() {
+        let mut points = square(0.0, 0.0, 2.0);
+        assert_close(signed_area(&points), 8.0);
+        points.reverse();
+        assert_close(signed_area(&points), -8.0);
+        assert_close(signed_area(&[]), 0.0);
+    }
+
+    #[test]
+    fn fills_a_concave_contour() {
+        let l = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+        assert_close(filled_area(&[l], FillRule::NonZero), 3.0);
+    }
+
+    #[test]
+    fn holes_depend_on_the_rule() {
+        let outer = square(0.0, 0.0, 10.0);
+        let mut inner = square(3.0, 3.0, 4.0);
+        let same = [outer.clone(), inner.clone()];
+        assert_close(filled_area(&same, FillRule::EvenOdd), 84.0);
+        assert_close(filled_area(&same, FillRule::NonZero), 100.0);
+
+        inner.reverse();
+        let opposite = [outer, inner];
+        assert_close(filled_area(&opposite, FillRule::NonZero), 84.0);
+    }
+
+    #[test]
+    fn splits_self_intersections() {
+        // A bow tie made of two triangles that meet at (1, 1).
+        let bow_tie = vec![(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)];
+        assert_close(filled_area(&[bow_tie], FillRule::NonZero), 2.0);
+    }
+
+    #[test]
+    fn skips_points_that_are_not_finite() {
+        let mut points = square(0.0, 0.0, 1.0);
+        points.insert(2, (::std::f32::NAN, 0.5));
+        points.push((0.5, ::std::f32::INFINITY));
+        assert_close(filled_area(&[points], FillRule::NonZero), 1.0);
+    }
+
+    #[test]
+    fn ignores_degenerate_contours() {
+        let line = vec![(0.0, 0.0), (1.0, 1.0)];
+        assert_close(filled_area(&[line], FillRule::NonZero), 0.0);
+    }
+
+    fn assert_points(a: &[Point], b: &[Point]) {
+        assert_eq!(a.len(), b.len(), "{:?} != {:?}", a, b);
+        for (p, q) in a.iter().zip(b.iter()) {
+            assert!((p.0 - q.0).abs() < 1e-3 && (p.1 - q.1).abs() < 1e-3, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn dash_splits_a_line() {
+        let pieces = Dash::new(&[2.0, 3.0], 0.0).split(&[(0.0, 0.0), (10.0, 0.0)], false);
+        assert_eq!(pieces.len(), 2);
+        assert_points(&pieces[0], &[(0.0, 0.0), (2.0, 0.0)]);
+        assert_points(&pieces[1], &[(5.0, 0.0), (7.0, 0.0)]);
+    }
+
+    #[test]
+    fn dash_offset_shifts_the_pattern() {
+        let pieces = Dash::new(&[2.0, 3.0], 1.0).split(&[(0.0, 0.0), (10.0, 0.0)], false);
+        assert_points(&pieces[0], &[(0.0, 0.0), (1.0, 0.0)]);
+        assert_points(&pieces[1], &[(4.0, 0.0), (6.0, 0.0)]);
+        assert_points(&pieces[2], &[(9.0, 0.0), (10.0, 0.0)]);
+
+        // Negative offsets wrap around the pattern.
+        let pieces = Dash::new(&[2.0, 3.0], -4.0).split(&[(0.0, 0.0), (10.0, 0.0)], false);
+        assert_points(&pieces[0], &[(0.0, 0.0), (1.0, 0.0)]);
+    }
+
+    #[test]
+    fn dash_continues_around_corners() {
+        let pieces = Dash::new(&[4.0, 4.0], 0.0).split(&[(0.0, 0.0), (2.0, 0.0), (2.0, 10.0)], false);
+        assert_points(&pieces[0], &[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)]);
+        assert_points(&pieces[1], &[(2.0, 6.0), (2.0, 10.0)]);
+    }
+
+    #[test]
+    fn tiny_dashes_on_long_lines_are_stretched() {
+        let line = [(0.0, 0.0), (1000.0, 0.0)];
+        let pieces = Dash::new(&[1e-6, 1e-6], 0.0).split(&line, false);
+        assert!(pieces.len() <= MAX_DASH_PERIODS as usize, "{} pieces", pieces.len());
+        assert_eq!(pieces[0][0], (0.0, 0.0));
+        let pieces = Dash::new(&[10.0, 1e-9], 0.0).split(&line, false);
+        assert!(pieces.len() <= 200, "{} pieces", pieces.len());
+    }
+
+    #[test]
+    fn dash_with_odd_lengths_repeats() {
+        assert_eq!(Dash::new(&[1.0], 0.0).split(&[(0.0, 0.0), (4.0, 0.0)], false).len(), 2);
+    }
+
+    #[test]
+    fn dash_joins_pieces_across_the_start_of_closed_lines() {
+        // A 4 by 4 square is 16 long, so the last dash runs into the first.
+        let square = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let pieces = Dash::new(&[3.0, 2.0], 0.0).split(&square, true);
+        let last = pieces.last().unwrap();
+        assert_points(last, &[(0.0, 1.0), (0.0, 0.0), (3.0, 0.0)]);
+        assert_eq!(pieces.len(), 3);
+    }
+
+    #[test]
+    fn zero_length_dashes_make_dots() {
+        let pieces = Dash::new(&[0.0, 5.0], 0.0).split(&[(0.0, 0.0), (10.0, 0.0)], false);
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            assert_eq!(piece.len(), 2);
+            assert!(piece[1].0 - piece[0].0 > 0.0);
+        }
+    }
+
+    #[test]
+    fn solid_patterns_return_the_whole_line() {
+        let line = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)];
+        assert_eq!(Dash::new(&[], 0.0).split(&line, false), vec![line.to_vec()]);
+        assert_eq!(Dash::new(&[0.0, 0.0], 0.0).split(&line, false), vec![line.to_vec()]);
+    }
+
+}
@@ -0,0 +1,1249 @@
+use rustc_serialize::json::Json;
+use rustc_serialize::base64::FromBase64;
+
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use glium::index::PrimitiveType::TrianglesList;
+
+use super::accessors::StateLike;
+use super::affine::Affine2;
+use super::canvas::{Canvas, Drawable};
+use super::error::{LuxError, LuxResult};
+use super::gfx_integration::TexVertex;
+use super::sprite::{IntoSprite, Sprite};
+use super::types::{Float, Idx};
+use super::xml::{self, Element};
+
+const FLIPPED_HORIZONTALLY: u32 = 0x80000000;
+const FLIPPED_VERTICALLY: u32 = 0x40000000;
+const FLIPPED_DIAGONALLY: u32 = 0x20000000;
+/// Clears the flip flags, along with the flag that Tiled uses to rotate
+/// hexagonal tiles.
+const GID_MASK: u32 = 0x0FFFFFFF;
+
+/// The width and height of a chunk of cached tile geometry, in tiles.
+pub const CHUNK_SIZE: u32 = 16;
+
+/// A single cell of a tile layer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Tile {
+    /// The global id of the tile, or 0 for an empty cell.
+    pub gid: u32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Flips the tile across its top left to bottom right diagonal.  This
+    /// happens before the horizontal and vertical flips.
+    pub flip_diagonal: bool,
+}
+
+/// An image that is divided into tiles.
+#[derive(Clone, Debug)]
+pub struct Tileset {
+    /// The global id of the first tile in the set.
+    pub first_gid: u32,
+    pub name: String,
+    pub tile_size: (u32, u32),
+    /// The space between tiles in the image.
+    pub spacing: u32,
+    /// The space around the edges of the image.
+    pub margin: u32,
+    pub columns: u32,
+    /// The number of tiles in the set.  Zero means that the count isn't
+    /// known, and the set covers every id up to the next tileset.
+    pub tile_count: u32,
+    /// The path to the image, relative to the map file.
+    pub image: Option<String>,
+    pub image_size: (u32, u32),
+    /// The file that defines this tileset, if it is kept outside of the
+    /// map.  `load_tilemap` reads these.
+    pub source: Option<String>,
+    /// The image that tiles are drawn from.  Tiles from tilesets without a
+    /// sprite are not drawn.
+    pub sprite: Option<Sprite>,
+}
+
+/// A grid of tiles.
+#[derive(Clone, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    /// The size of the layer in tiles.
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub opacity: Float,
+    /// How far the layer is drawn from the origin of the map, in pixels.
+    pub offset: (Float, Float),
+    pub properties: HashMap<String, String>,
+    tiles: Vec<Tile>,
+    chunks: RefCell<HashMap<(u32, u32), Vec<ChunkMesh>>>,
+}
+
+/// The geometry for every tile in a chunk that comes from one tileset.
+#[derive(Clone, Debug)]
+struct ChunkMesh {
+    tileset: usize,
+    vertices: Vec<TexVertex>,
+    idxs: Vec<Idx>,
+}
+
+/// The shape of a `MapObject`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    /// A closed polygon with points relative to the object's position.
+    Polygon(Vec<(Float, Float)>),
+    /// An open line with points relative to the object's position.
+    Polyline(Vec<(Float, Float)>),
+}
+
+/// A shape or tile that was placed on an object layer.
+#[derive(Clone, Debug)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    /// The type (or class) that was given to the object in Tiled.
+    pub kind: String,
+    pub x: Float,
+    pub y: Float,
+    pub w: Float,
+    pub h: Float,
+    /// The rotation in degrees, clockwise.
+    pub rotation: Float,
+    /// The tile that the object shows, if it is a tile object.
+    pub tile: Option<Tile>,
+    pub visible: bool,
+    pub shape: ObjectShape,
+    pub properties: HashMap<String, String>,
+}
+
+/// A list of objects.
+#[derive(Clone, Debug)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub visible: bool,
+    pub opacity: Float,
+    pub offset: (Float, Float),
+    pub properties: HashMap<String, String>,
+    pub objects: Vec<MapObject>,
+}
+
+/// A layer of a map.  Group layers are flattened into the layers that they
+/// contain.
+#[derive(Clone, Debug)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+/// An orthogonal map made in the Tiled map editor.
+///
+/// Maps can be read from both TMX and JSON files.  Only finite maps with
+/// uncompressed tile data are supported.
+#[derive(Clone, Debug)]
+pub struct TileMap {
+    /// The size of the map in tiles.
+    pub size: (u32, u32),
+    /// The size of a grid cell in pixels.
+    pub tile_size: (u32, u32),
+    pub tilesets: Vec<Tileset>,
+    /// Every layer, from the bottom to the top.
+    pub layers: Vec<Layer>,
+    pub properties: HashMap<String, String>,
+}
+
+/// Draws the tile layers of a `TileMap`.
+///
+/// Tiles are drawn in chunks of `CHUNK_SIZE` by `CHUNK_SIZE` tiles.  The
+/// geometry of a chunk is built the first time that it is drawn and is kept
+/// until one of its tiles changes, so drawing a static layer doesn't
+/// re-tessellate it.  When `view` is set, only the chunks that overlap it
+/// are drawn.
+#[derive(Copy, Clone)]
+pub struct MapView<'a> {
+    pub map: Option<&'a TileMap>,
+    /// The layer to draw.  `None` draws every visible tile layer.
+    pub layer: Option<usize>,
+    /// Where the top left corner of the map is drawn.
+    pub x: Float,
+    pub y: Float,
+    /// The part of the map that can be seen, as (x, y, w, h) in map pixels.
+    pub view: Option<(Float, Float, Float, Float)>,
+    pub color: [f32; 4],
+    pub transform: Option<[[Float; 4]; 4]>,
+}
+
+impl Default for MapView<'static> {
+    fn default() -> MapView<'static> {
+        MapView {
+            map: None,
+            layer: None,
+            x: 0.0,
+            y: 0.0,
+            view: None,
+            color: [1.0, 1.0, 1.0, 1.0],
+            transform: None,
+        }
+    }
+}
+
+impl Tile {
+    /// Splits a global id that was read from a map into the id and its
+    /// flip flags.
+    pub fn from_raw(raw: u32) -> Tile {
+        Tile {
+            gid: raw & GID_MASK,
+            flip_x: raw & FLIPPED_HORIZONTALLY != 0,
+            flip_y: raw & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: raw & FLIPPED_DIAGONALLY != 0,
+        }
+    }
+
+    /// Returns true if there is no tile in this cell.
+    pub fn is_empty(&self) -> bool {
+        self.gid == 0
+    }
+
+    /// Maps a corner of the cell, (0, 0) to (1, 1), to the corner of the
+    /// tile image that is drawn there.
+    fn corner(&self, (mut x, mut y): (Float, Float)) -> (Float, Float) {
+        if self.flip_y { y = 1.0 - y; }
+        if self.flip_x { x = 1.0 - x; }
+        if self.flip_diagonal { (y, x) } else { (x, y) }
+    }
+}
+
+impl Tileset {
+    /// Returns the rectangle of a tile in the tileset image as
+    /// (x, y, w, h) in pixels.
+    pub fn tile_rect(&self, id: u32) -> (u32, u32, u32, u32) {
+        let columns = if self.columns > 0 { self.columns } else { 1 };
+        let (tw, th) = self.tile_size;
+        let (col, row) = (id % columns, id / columns);
+        (self.margin + col * (tw + self.spacing),
+         self.margin + row * (th + self.spacing),
+         tw, th)
+    }
+}
+
+impl TileLayer {
+    /// Creates a layer where every cell is empty.
+    ///
+    /// ## Panics
+    /// Panics if `width * height` cells don't fit in memory.
+    pub fn new<S: Into<String>>(name: S, width: u32, height: u32) -> TileLayer {
+        let count = tile_count((width, height)).expect("tile layer is too big");
+        TileLayer::with_tiles(name.into(), (width, height), vec![Tile::default(); count])
+    }
+
+    fn with_tiles(name: String, size: (u32, u32), tiles: Vec<Tile>) -> TileLayer {
+        TileLayer {
+            name: name,
+            size: size,
+            visible: true,
+            opacity: 1.0,
+            offset: (0.0, 0.0),
+            properties: HashMap::new(),
+            tiles: tiles,
+            chunks: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the tile at a cell.
+    pub fn tile(&self, x: u32, y: u32) -> Option<Tile> {
+        if x < self.size.0 && y < self.size.1 {
+            Some(self.tiles[(y * self.size.0 + x) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Changes the tile at a cell.  Only the chunk that contains the cell
+    /// will be rebuilt.
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Tile) {
+        if x < self.size.0 && y < self.size.1 {
+            self.tiles[(y * self.size.0 + x) as usize] = tile;
+            self.chunks.borrow_mut().remove(&(x / CHUNK_SIZE, y / CHUNK_SIZE));
+        }
+    }
+
+    /// Returns every tile in the layer, row by row.
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles[..]
+    }
+
+    /// Throws away all cached geometry.
+    pub fn clear_cache(&self) {
+        self.chunks.borrow_mut().clear();
+    }
+
+    fn build_chunk(&self, map: &TileMap, (cx, cy): (u32, u32)) -> Vec<ChunkMesh> {
+        let mut meshes: Vec<ChunkMesh> = vec![];
+        let (map_tw, map_th) = (map.tile_size.0 as Float, map.tile_size.1 as Float);
+
+        let x_end = cmp::min((cx + 1) * CHUNK_SIZE, self.size.0);
+        let y_end = cmp::min((cy + 1) * CHUNK_SIZE, self.size.1);
+        for y in cy * CHUNK_SIZE .. y_end {
+            for x in cx * CHUNK_SIZE .. x_end {
+                let tile = self.tiles[(y * self.size.0 + x) as usize];
+                let index = match map.tileset_index(tile.gid) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let tileset = &map.tilesets[index];
+                let sprite = match tileset.sprite {
+                    Some(ref sprite) => sprite,
+                    None => continue,
+                };
+
+                // Texture coordinates of the tile
+                let (sx, sy, sw, sh) = tileset.tile_rect(tile.gid - tileset.first_gid);
+                let bounds = sprite.bounds();
+                let (w, h) = sprite.size();
+                let (u0, v0) = (bounds[0][0], bounds[0][1]);
+                let (du, dv) = (bounds[3][0] - u0, bounds[3][1] - v0);
+                let uv = |(cx, cy): (Float, Float)| {
+                    [u0 + du * (sx as Float + cx * sw as Float) / w,
+                     v0 + dv * (sy as Float + cy * sh as Float) / h]
+                };
+
+                // Tiles that are bigger than the grid stick out of the top
+                // of their cell.
+                let left = x as Float * map_tw;
+                let bottom = (y + 1) as Float * map_th;
+                let (right, top) = (left + sw as Float, bottom - sh as Float);
+
+                let mesh_index = match meshes.iter().position(|m| m.tileset == index) {
+                    Some(i) => i,
+                    None => {
+                        meshes.push(ChunkMesh { tileset: index, vertices: vec![], idxs: vec![] });
+                        meshes.len() - 1
+                    }
+                };
+                let mesh = &mut meshes[mesh_index];
+                let base = mesh.vertices.len() as Idx;
+                let white = [1.0, 1.0, 1.0, 1.0];
+                mesh.vertices.push(TexVertex {
+                    pos: [right, top], tex_coords: uv(tile.corner((1.0, 0.0))), color: white });
+                mesh.vertices.push(TexVertex {
+                    pos: [left, top], tex_coords: uv(tile.corner((0.0, 0.0))), color: white });
+                mesh.vertices.push(TexVertex {
+                    pos: [left, bottom], tex_coords: uv(tile.corner((0.0, 1.0))), color: white });
+                mesh.vertices.push(TexVertex {
+                    pos: [right, bottom], tex_coords: uv(tile.corner((1.0, 1.0))), color: white });
+                mesh.idxs.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+            }
+        }
+        meshes
+    }
+}
+
+impl Layer {
+    /// Returns the name of the layer.
+    pub fn name(&self) -> &str {
+        match self {
+            &Layer::Tiles(ref l) => &l.name[..],
+            &Layer::Objects(ref l) => &l.name[..],
+        }
+    }
+
+    /// Returns true if the layer is visible.
+    pub fn visible(&self) -> bool {
+        match self {
+            &Layer::Tiles(ref l) => l.visible,
+            &Layer::Objects(ref l) => l.visible,
+        }
+    }
+}
+
+impl TileMap {
+    /// Parses a map that was saved as JSON.
+    pub fn from_json(source: &str) -> LuxResult<TileMap> {
+        let root = try!(Json::from_str(source));
+        map_from_json(&root)
+    }
+
+    /// Parses a map that was saved as TMX.
+    pub fn from_tmx(source: &str) -> LuxResult<TileMap> {
+        let root = try!(xml::parse(source));
+        map_from_tmx(&root)
+    }
+
+    /// Returns the size of the map in pixels.
+    pub fn pixel_size(&self) -> (Float, Float) {
+        (self.size.0 as Float * self.tile_size.0 as Float,
+         self.size.1 as Float * self.tile_size.1 as Float)
+    }
+
+    /// Returns the index of the tileset that a global tile id belongs to.
+    pub fn tileset_index(&self, gid: u32) -> Option<usize> {
+        if gid == 0 {
+            return None;
+        }
+        let mut found = None;
+        for (i, tileset) in self.tilesets.iter().enumerate() {
+            // A tileset without a count runs up to the next one.
+            let in_range = tileset.first_gid <= gid &&
+                (tileset.tile_count == 0 || gid - tileset.first_gid < tileset.tile_count);
+            if in_range {
+                found = Some(i);
+            }
+        }
+        found
+    }
+
+    /// Finds a layer by name.
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name() == name)
+    }
+
+    /// Finds a tile layer by name.
+    pub fn tile_layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().filter_map(|l| match l {
+            &Layer::Tiles(ref l) if l.name == name => Some(l),
+            _ => None,
+        }).next()
+    }
+
+    /// Finds a tile layer by name so that it can be changed.
+    pub fn tile_layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().filter_map(|l| match l {
+            &mut Layer::Tiles(ref mut l) if l.name == name => Some(l),
+            _ => None,
+        }).next()
+    }
+
+    /// Finds an object layer by name.
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        self.layers.iter().filter_map(|l| match l {
+            &Layer::Objects(ref l) if l.name == name => Some(l),
+            _ => None,
+        }).next()
+    }
+
+    /// Sets the image of a tileset and throws away any geometry that was
+    /// built without it.
+    pub fn set_tileset_sprite(&mut self, index: usize, sprite: Sprite) {
+        self.tilesets[index].sprite = Some(sprite);
+        for layer in &self.layers {
+            if let &Layer::Tiles(ref l) = layer {
+                l.clear_cache();
+            }
+        }
+    }
+
+    fn draw_layer<C: Canvas>(&self, layer: &TileLayer, view: &MapView, canvas: &mut C)
+    -> LuxResult<()> {
+        if layer.size.0 == 0 || layer.size.1 == 0 || self.tile_size.0 == 0 || self.tile_size.1 == 0 {
+            return Ok(());
+        }
+
+        let chunk_w = CHUNK_SIZE as Float * self.tile_size.0 as Float;
+        let chunk_h = CHUNK_SIZE as Float * self.tile_size.1 as Float;
+        let chunks_x = layer.size.0 / CHUNK_SIZE + if layer.size.0 % CHUNK_SIZE > 0 { 1 } else { 0 };
+        let chunks_y = layer.size.1 / CHUNK_SIZE + if layer.size.1 % CHUNK_SIZE > 0 { 1 } else { 0 };
+
+        let (x0, y0, x1, y1) = match view.view {
+            Some((vx, vy, vw, vh)) => {
+                // Tiles can be bigger than the grid, so look a little past
+                // the edges of the view.
+                let (pad_x, pad_y) = self.tilesets.iter().fold((0, 0), |(w, h), t| {
+                    (cmp::max(w, t.tile_size.0), cmp::max(h, t.tile_size.1))
+                });
+                let (lx, ly) = (vx - layer.offset.0, vy - layer.offset.1);
+                let first_x = ((lx - pad_x as Float) / chunk_w).floor().max(0.0) as u32;
+                let first_y = (ly / chunk_h).floor().max(0.0) as u32;
+                let last_x = ((lx + vw) / chunk_w).ceil().max(0.0) as u32;
+                let last_y = ((ly + vh + pad_y as Float) / chunk_h).ceil().max(0.0) as u32;
+                (first_x, first_y, cmp::min(last_x, chunks_x), cmp::min(last_y, chunks_y))
+            }
+            None => (0, 0, chunks_x, chunks_y),
+        };
+
+        {
+            let mut chunks = layer.chunks.borrow_mut();
+            for cy in y0 .. y1 {
+                for cx in x0 .. x1 {
+                    if !chunks.contains_key(&(cx, cy)) {
+                        chunks.insert((cx, cy), layer.build_chunk(self, (cx, cy)));
+                    }
+                }
+            }
+        }
+
+        let transform = view.transform.map(Affine2::from)
+                                      .unwrap_or(Affine2::identity())
+                                      .translate(view.x + layer.offset.0, view.y + layer.offset.1);
+        let color = [view.color[0], view.color[1], view.color[2],
+                     view.color[3] * layer.opacity];
+
+        let chunks = layer.chunks.borrow();
+        for cy in y0 .. y1 {
+            for cx in x0 .. x1 {
+                for mesh in &chunks[&(cx, cy)] {
+                    let sprite = match self.tilesets[mesh.tileset].sprite {
+                        Some(ref sprite) => sprite,
+                        None => continue,
+                    };
+                    try!(canvas.draw_tex(TrianglesList, &mesh.vertices[..], Some(&mesh.idxs[..]),
+                                         Some(transform.into()), sprite.texture(), Some(color),
+                                         Some(sprite.sampler())));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl <'a> Drawable for MapView<'a> {
+    fn draw<C: Canvas>(self, canvas: &mut C) -> LuxResult<()> {
+        let map = match self.map {
+            Some(map) => map,
+            None => return Ok(()),
+        };
+
+        match self.layer {
+            Some(index) => {
+                if let Some(&Layer::Tiles(ref layer)) = map.layers.get(index) {
+                    try!(map.draw_layer(layer, &self, canvas));
+                }
+            }
+            None => {
+                for layer in &map.layers {
+                    if let &Layer::Tiles(ref layer) = layer {
+                        if layer.visible {
+                            try!(map.draw_layer(layer, &self, canvas));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Loads a TMX or JSON map along with its external tilesets and tileset
+/// images.
+///
+/// Files that end in `.tmx` are read as TMX and everything else is read as
+/// JSON.
+///
+/// ```rust,no_run
+///# extern crate lux;
+///# use lux::prelude::*;
+///# use lux::graphics::{load_tilemap, MapView};
+///# fn main() {
+///# let mut window = Window::new_with_defaults().unwrap();
+/// let map = load_tilemap(&window, "./assets/level1.tmx").unwrap();
+/// while window.is_open() {
+///     let mut frame = window.cleared_frame(rgb(0.0, 0.0, 0.0));
+///     frame.draw(MapView { map: Some(&map), .. Default::default() }).unwrap();
+/// }
+///# }
+/// ```
+pub fn load_tilemap<D, P: ?Sized>(d: &D, path: &P) -> LuxResult<TileMap>
+where D: StateLike, P: AsRef<Path> {
+    let path = path.as_ref();
+    let source = try!(read_file(path));
+    let mut map = if is_tmx(path) {
+        try!(TileMap::from_tmx(&source))
+    } else {
+        try!(TileMap::from_json(&source))
+    };
+
+    for tileset in &mut map.tilesets {
+        if let Some(source) = tileset.source.take() {
+            let tileset_path = path.with_file_name(&source);
+            let text = try!(read_file(&tileset_path));
+            let mut external = if is_tmx(&tileset_path) {
+                try!(tileset_from_tmx(&try!(xml::parse(&text)), tileset.first_gid))
+            } else {
+                try!(tileset_from_json(&try!(Json::from_str(&text)), tileset.first_gid))
+            };
+
+            // The image is relative to the tileset file, not to the map.
+            if let Some(image) = external.image.take() {
+                let relative = Path::new(&source).with_file_name(image);
+                external.image = Some(relative.to_string_lossy().into_owned());
+            }
+            *tileset = external;
+        }
+
+        if let Some(image) = tileset.image.clone() {
+            let image_path = path.with_file_name(image);
+            tileset.sprite = Some(try!(image_path.as_path().into_sprite(d)));
+        }
+    }
+
+    Ok(map)
+}
+
+fn is_tmx(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("tmx") | Some("tsx") => true,
+        _ => false,
+    }
+}
+
+fn read_file(path: &Path) -> LuxResult<String> {
+    let mut source = String::new();
+    try!(try!(File::open(path)).read_to_string(&mut source));
+    Ok(source)
+}
+
+fn bad_map(message: &str) -> LuxError {
+    LuxError::SerializationError(format!("Invalid tile map: {}", message))
+}
+
+/// Turns the bytes of base64 encoded tile data into global ids.
+fn decode_base64(data: &str) -> LuxResult<Vec<u32>> {
+    let bytes = try!(data.trim().from_base64().map_err(|e| bad_map(&format!("{}", e))));
+    if bytes.len() % 4 != 0 {
+        return Err(bad_map("tile data must be made of 32 bit ids"));
+    }
+    Ok(bytes.chunks(4).map(|b| {
+        b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24
+    }).collect())
+}
+
+/// Returns how many cells a layer of `size` has, or `None` if that
+/// doesn't fit in a `usize`.
+fn tile_count(size: (u32, u32)) -> Option<usize> {
+    (size.0 as usize).checked_mul(size.1 as usize)
+}
+
+fn check_tile_count(gids: &[u32], size: (u32, u32), name: &str) -> LuxResult<()> {
+    match tile_count(size) {
+        Some(count) if gids.len() == count => Ok(()),
+        Some(count) => Err(bad_map(&format!("layer `{}` has {} tiles instead of {}",
+                                            name, gids.len(), count))),
+        None => Err(bad_map(&format!("layer `{}` is too big", name))),
+    }
+}
+
+/// Checks a tile width and height that were read from a map.
+fn tile_size(width: f64, height: f64) -> LuxResult<(u32, u32)> {
+    if width >= 1.0 && height >= 1.0 {
+        Ok((width as u32, height as u32))
+    } else {
+        Err(bad_map("tiles must be at least one pixel wide and tall"))
+    }
+}
+
+// JSON
+
+fn json_number(obj: &Json, key: &str) -> LuxResult<f64> {
+    obj.find(key).and_then(Json::as_f64)
+       .ok_or_else(|| bad_map(&format!("`{}` must be a number", key)))
+}
+
+fn json_number_or(obj: &Json, key: &str, default: f64) -> f64 {
+    obj.find(key).and_then(Json::as_f64).unwrap_or(default)
+}
+
+fn json_string(obj: &Json, key: &str) -> String {
+    obj.find(key).and_then(Json::as_string).unwrap_or("").to_string()
+}
+
+fn json_value_string(value: &Json) -> String {
+    match value {
+        &Json::String(ref s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn json_properties(obj: &Json) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    match obj.find("properties") {
+        // Tiled 1.2 and later
+        Some(&Json::Array(ref list)) => {
+            for property in list {
+                if let (Some(name), Some(value)) = (property.find("name").and_then(Json::as_string),
+                                                    property.find("value")) {
+                    properties.insert(name.to_string(), json_value_string(value));
+                }
+            }
+        }
+        // Older versions
+        Some(&Json::Object(ref map)) => {
+            for (name, value) in map {
+                properties.insert(name.clone(), json_value_string(value));
+            }
+        }
+        _ => {}
+    }
+    properties
+}
+
+fn map_from_json(root: &Json) -> LuxResult<TileMap> {
+    if let Some(orientation) = root.find("orientation").and_then(Json::as_string) {
+        if orientation != "orthogonal" {
+            return Err(bad_map(&format!("{} maps are not supported", orientation)));
+        }
+    }
+    if root.find("infinite").and_then(Json::as_boolean) == Some(true) {
+        return Err(bad_map("infinite maps are not supported"));
+    }
+
+    let mut tilesets = vec![];
+    if let Some(list) = root.find("tilesets").and_then(Json::as_array) {
+        for tileset in list {
+            let first_gid = try!(json_number(tileset, "firstgid")) as u32;
+            tilesets.push(try!(tileset_from_json(tileset, first_gid)));
+        }
+    }
+
+    let mut layers = vec![];
+    if let Some(list) = root.find("layers").and_then(Json::as_array) {
+        try!(layers_from_json(list, (0.0, 0.0), 1.0, true, &mut layers));
+    }
+
+    Ok(TileMap {
+        size: (try!(json_number(root, "width")) as u32, try!(json_number(root, "height")) as u32),
+        tile_size: try!(tile_size(try!(json_number(root, "tilewidth")),
+                                  try!(json_number(root, "tileheight")))),
+        tilesets: tilesets,
+        layers: layers,
+        properties: json_properties(root),
+    })
+}
+
+fn tileset_from_json(tileset: &Json, first_gid: u32) -> LuxResult<Tileset> {
+    if let Some(source) = tileset.find("source").and_then(Json::as_string) {
+        return Ok(external_tileset(first_gid, source));
+    }
+
+    let tile_size = try!(tile_size(try!(json_number(tileset, "tilewidth")),
+                                   try!(json_number(tileset, "tileheight"))));
+    let image_size = (json_number_or(tileset, "imagewidth", 0.0) as u32,
+                      json_number_or(tileset, "imageheight", 0.0) as u32);
+    let spacing = json_number_or(tileset, "spacing", 0.0) as u32;
+    let margin = json_number_or(tileset, "margin", 0.0) as u32;
+    let columns = match tileset.find("columns").and_then(Json::as_f64) {
+        Some(columns) => columns as u32,
+        None => columns_in(image_size.0, tile_size.0, spacing, margin),
+    };
+
+    Ok(Tileset {
+        first_gid: first_gid,
+        name: json_string(tileset, "name"),
+        tile_size: tile_size,
+        spacing: spacing,
+        margin: margin,
+        columns: columns,
+        tile_count: match tileset.find("tilecount").and_then(Json::as_f64) {
+            Some(count) => count as u32,
+            None => tiles_in(image_size, tile_size, spacing, margin),
+        },
+        image: tileset.find("image").and_then(Json::as_string).map(String::from),
+        image_size: image_size,
+        source: None,
+        sprite: None,
+    })
+}
+
+fn layers_from_json(list: &[Json], offset: (Float, Float), opacity: Float, visible: bool,
+                    out: &mut Vec<Layer>) -> LuxResult<()> {
+    for layer in list {
+        let name = json_string(layer, "name");
+        let offset = (offset.0 + json_number_or(layer, "offsetx", 0.0) as Float,
+                      offset.1 + json_number_or(layer, "offsety", 0.0) as Float);
+        let opacity = opacity * json_number_or(layer, "opacity", 1.0) as Float;
+        let visible = visible && layer.find("visible").and_then(Json::as_boolean).unwrap_or(true);
+
+        match layer.find("type").and_then(Json::as_string) {
+            Some("tilelayer") => {
+                let size = (try!(json_number(layer, "width")) as u32,
+                            try!(json_number(layer, "height")) as u32);
+                if layer.find("compression").and_then(Json::as_string).map(|c| !c.is_empty()) == Some(true) {
+                    return Err(bad_map(&format!("layer `{}` uses compressed data", name)));
+                }
+                let gids: Vec<u32> = match layer.find("data") {
+                    Some(&Json::Array(ref data)) => {
+                        data.iter().map(|g| g.as_f64().unwrap_or(0.0) as u32).collect()
+                    }
+                    Some(&Json::String(ref data)) => try!(decode_base64(data)),
+                    _ => return Err(bad_map(&format!("layer `{}` has no tile data", name))),
+                };
+                try!(check_tile_count(&gids, size, &name));
+
+                let tiles = gids.into_iter().map(Tile::from_raw).collect();
+                let mut tile_layer = TileLayer::with_tiles(name, size, tiles);
+                tile_layer.visible = visible;
+                tile_layer.opacity = opacity;
+                tile_layer.offset = offset;
+                tile_layer.properties = json_properties(layer);
+                out.push(Layer::Tiles(tile_layer));
+            }
+            Some("objectgroup") => {
+                let mut objects = vec![];
+                if let Some(list) = layer.find("objects").and_then(Json::as_array) {
+                    for object in list {
+                        objects.push(try!(object_from_json(object)));
+                    }
+                }
+                out.push(Layer::Objects(ObjectLayer {
+                    name: name,
+                    visible: visible,
+                    opacity: opacity,
+                    offset: offset,
+                    properties: json_properties(layer),
+                    objects: objects,
+                }));
+            }
+            Some("group") => {
+                if let Some(list) = layer.find("layers").and_then(Json::as_array) {
+                    try!(layers_from_json(list, offset, opacity, visible, out));
+                }
+            }
+            // Image layers aren't supported.
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn json_points(list: &[Json]) -> Vec<(Float, Float)> {
+    list.iter().map(|p| (json_number_or(p, "x", 0.0) as Float,
+                         json_number_or(p, "y", 0.0) as Float)).collect()
+}
+
+fn object_from_json(object: &Json) -> LuxResult<MapObject> {
+    let shape = if let Some(points) = object.find("polygon").and_then(Json::as_array) {
+        ObjectShape::Polygon(json_points(points))
+    } else if let Some(points) = object.find("polyline").and_then(Json::as_array) {
+        ObjectShape::Polyline(json_points(points))
+    } else if object.find("ellipse").and_then(Json::as_boolean) == Some(true) {
+        ObjectShape::Ellipse
+    } else if object.find("point").and_then(Json::as_boolean) == Some(true) {
+        ObjectShape::Point
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    let kind = match object.find("class").and_then(Json::as_string) {
+        Some(class) => class.to_string(),
+        None => json_string(object, "type"),
+    };
+
+    Ok(MapObject {
+        id: json_number_or(object, "id", 0.0) as u32,
+        name: json_string(object, "name"),
+        kind: kind,
+        x: try!(json_number(object, "x")) as Float,
+        y: try!(json_number(object, "y")) as Float,
+        w: json_number_or(object, "width", 0.0) as Float,
+        h: json_number_or(object, "height", 0.0) as Float,
+        rotation: json_number_or(object, "rotation", 0.0) as Float,
+        tile: object.find("gid").and_then(Json::as_f64).map(|g| Tile::from_raw(g as u32)),
+        visible: object.find("visible").and_then(Json::as_boolean).unwrap_or(true),
+        shape: shape,
+        properties: json_properties(object),
+    })
+}
+
+// TMX
+
+fn xml_number(e: &Element, key: &str) -> LuxResult<f64> {
+    e.attr(key).and_then(|v| v.trim().parse().ok())
+     .ok_or_else(|| bad_map(&format!("`{}` on <{}> must be a number", key, e.name)))
+}
+
+fn xml_number_or(e: &Element, key: &str, default: f64) -> f64 {
+    e.attr(key).and_then(|v| v.trim().parse().ok()).unwrap_or(default)
+}
+
+fn xml_string(e: &Element, key: &str) -> String {
+    e.attr(key).unwrap_or("").to_string()
+}
+
+fn xml_properties(e: &Element) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    if let Some(list) = e.child("properties") {
+        for property in list.children_named("property") {
+            // Multi-line strings are stored as text instead of an attribute.
+            let value = match property.attr("value") {
+                Some(value) => value.to_string(),
+                None => property.text.clone(),
+            };
+            properties.insert(xml_string(property, "name"), value);
+        }
+    }
+    properties
+}
+
+fn map_from_tmx(root: &Element) -> LuxResult<TileMap> {
+    if root.name != "map" {
+        return Err(bad_map("the root element must be <map>"));
+    }
+    if let Some(orientation) = root.attr("orientation") {
+        if orientation != "orthogonal" {
+            return Err(bad_map(&format!("{} maps are not supported", orientation)));
+        }
+    }
+    if root.attr("infinite") == Some("1") {
+        return Err(bad_map("infinite maps are not supported"));
+    }
+
+    let mut tilesets = vec![];
+    for tileset in root.children_named("tileset") {
+        let first_gid = try!(xml_number(tileset, "firstgid")) as u32;
+        tilesets.push(try!(tileset_from_tmx(tileset, first_gid)));
+    }
+
+    let mut layers = vec![];
+    try!(layers_from_tmx(root, (0.0, 0.0), 1.0, true, &mut layers));
+
+    Ok(TileMap {
+        size: (try!(xml_number(root, "width")) as u32, try!(xml_number(root, "height")) as u32),
+        tile_size: try!(tile_size(try!(xml_number(root, "tilewidth")),
+                                  try!(xml_number(root, "tileheight")))),
+        tilesets: tilesets,
+        layers: layers,
+        properties: xml_properties(root),
+    })
+}
+
+fn tileset_from_tmx(tileset: &Element, first_gid: u32) -> LuxResult<Tileset> {
+    if let Some(source) = tileset.attr("source") {
+        return Ok(external_tileset(first_gid, source));
+    }
+
+    let tile_size = try!(tile_size(try!(xml_number(tileset, "tilewidth")),
+                                   try!(xml_number(tileset, "tileheight"))));
+    let spacing = xml_number_or(tileset, "spacing", 0.0) as u32;
+    let margin = xml_number_or(tileset, "margin", 0.0) as u32;
+    let (image, image_size) = match tileset.child("image") {
+        Some(image) => (image.attr("source").map(String::from),
+                        (xml_number_or(image, "width", 0.0) as u32,
+                         xml_number_or(image, "height", 0.0) as u32)),
+        None => (None, (0, 0)),
+    };
+    let columns = match tileset.attr("columns").and_then(|c| c.parse().ok()) {
+        Some(columns) => columns,
+        None => columns_in(image_size.0, tile_size.0, spacing, margin),
+    };
+
+    Ok(Tileset {
+        first_gid: first_gid,
+        name: xml_string(tileset, "name"),
+        tile_size: tile_size,
+        spacing: spacing,
+        margin: margin,
+        columns: columns,
+        tile_count: match tileset.attr("tilecount").and_then(|c| c.parse().ok()) {
+            Some(count) => count,
+            None => tiles_in(image_size, tile_size, spacing, margin),
+        },
+        image: image,
+        image_size: image_size,
+        source: None,
+        sprite: None,
+    })
+}
+
+fn layers_from_tmx(parent: &Element, offset: (Float, Float), opacity: Float, visible: bool,
+                   out: &mut Vec<Layer>) -> LuxResult<()> {
+    for layer in &parent.children {
+        let name = xml_string(layer, "name");
+        let offset = (offset.0 + xml_number_or(layer, "offsetx", 0.0) as Float,
+                      offset.1 + xml_number_or(layer, "offsety", 0.0) as Float);
+        let opacity = opacity * xml_number_or(layer, "opacity", 1.0) as Float;
+        let visible = visible && layer.attr("visible") != Some("0");
+
+        match &layer.name[..] {
+            "layer" => {
+                let size = (try!(xml_number(layer, "width")) as u32,
+                            try!(xml_number(layer, "height")) as u32);
+                let data = match layer.child("data") {
+                    Some(data) => data,
+                    None => return Err(bad_map(&format!("layer `{}` has no tile data", name))),
+                };
+                if data.attr("compression").is_some() {
+                    return Err(bad_map(&format!("layer `{}` uses compressed data", name)));
+                }
+                let gids: Vec<u32> = match data.attr("encoding") {
+                    Some("csv") => {
+                        let mut gids = vec![];
+                        for gid in data.text.split(',') {
+                            let gid = gid.trim();
+                            if !gid.is_empty() {
+                                gids.push(try!(gid.parse().map_err(|_| {
+                                    bad_map(&format!("`{}` is not a tile id", gid))
+                                })));
+                            }
+                        }
+                        gids
+                    }
+                    Some("base64") => try!(decode_base64(&data.text)),
+                    Some(other) => {
+                        return Err(bad_map(&format!("unknown encoding `{}`", other)));
+                    }
+                    None => {
+                        data.children_named("tile").iter()
+                            .map(|t| xml_number_or(t, "gid", 0.0) as u32)
+                            .collect()
+                    }
+                };
+                try!(check_tile_count(&gids, size, &name));
+
+                let tiles = gids.into_iter().map(Tile::from_raw).collect();
+                let mut tile_layer = TileLayer::with_tiles(name, size, tiles);
+                tile_layer.visible = visible;
+                tile_layer.opacity = opacity;
+                tile_layer.offset = offset;
+                tile_layer.properties = xml_properties(layer);
+                out.push(Layer::Tiles(tile_layer));
+            }
+            "objectgroup" => {
+                let mut objects = vec![];
+                for object in layer.children_named("object") {
+                    objects.push(try!(object_from_tmx(object)));
+                }
+                out.push(Layer::Objects(ObjectLayer {
+                    name: name,
+                    visible: visible,
+                    opacity: opacity,
+                    offset: offset,
+                    properties: xml_properties(layer),
+                    objects: objects,
+                }));
+            }
+            "group" => {
+                try!(layers_from_tmx(layer, offset, opacity, visible, out));
+            }
+            // Image layers, tilesets and properties
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn xml_points(points: &str) -> LuxResult<Vec<(Float, Float)>> {
+    let mut out = vec![];
+    for pair in points.split_whitespace() {
+        let mut parts = pair.split(',').map(|p| p.parse::<Float>());
+        match (parts.next(), parts.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => out.push((x, y)),
+            _ => return Err(bad_map(&format!("`{}` is not a point", pair))),
+        }
+    }
+    Ok(out)
+}
+
+fn object_from_tmx(object: &Element) -> LuxResult<MapObject> {
+    let shape = if let Some(polygon) = object.child("polygon") {
+        ObjectShape::Polygon(try!(xml_points(polygon.attr("points").unwrap_or(""))))
+    } else if let Some(polyline) = object.child("polyline") {
+        ObjectShape::Polyline(try!(xml_points(polyline.attr("points").unwrap_or(""))))
+    } else if object.child("ellipse").is_some() {
+        ObjectShape::Ellipse
+    } else if object.child("point").is_some() {
+        ObjectShape::Point
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    let kind = match object.attr("class") {
+        Some(class) => class.to_string(),
+        None => xml_string(object, "type"),
+    };
+
+    Ok(MapObject {
+        id: xml_number_or(object, "id", 0.0) as u32,
+        name: xml_string(object, "name"),
+        kind: kind,
+        x: try!(xml_number(object, "x")) as Float,
+        y: try!(xml_number(object, "y")) as Float,
+        w: xml_number_or(object, "width", 0.0) as Float,
+        h: xml_number_or(object, "height", 0.0) as Float,
+        rotation: xml_number_or(object, "rotation", 0.0) as Float,
+        tile: object.attr("gid").and_then(|g| g.parse().ok()).map(Tile::from_raw),
+        visible: object.attr("visible") != Some("0"),
+        shape: shape,
+        properties: xml_properties(object),
+    })
+}
+
+// Shared
+
+fn external_tileset(first_gid: u32, source: &str) -> Tileset {
+    Tileset {
+        first_gid: first_gid,
+        name: String::new(),
+        tile_size: (0, 0),
+        spacing: 0,
+        margin: 0,
+        columns: 0,
+        tile_count: 0,
+        image: None,
+        image_size: (0, 0),
+        source: Some(source.to_string()),
+        sprite: None,
+    }
+}
+
+/// Works out how many tiles fit in an image for tilesets that don't say.
+fn tiles_in(image_size: (u32, u32), tile_size: (u32, u32), spacing: u32, margin: u32) -> u32 {
+    columns_in(image_size.0, tile_size.0, spacing, margin) *
+        columns_in(image_size.1, tile_size.1, spacing, margin)
+}
+
+/// Works out how many columns of tiles fit in an image for tilesets that
+/// don't say.
+fn columns_in(image_width: u32, tile_width: u32, spacing: u32, margin: u32) -> u32 {
+    if tile_width == 0 || image_width < margin * 2 + tile_width {
+        return 0;
+    }
+    (image_width - margin * 2 + spacing) / (tile_width + spacing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_tile_count, TileMap, Tile, ObjectShape};
+
+    fn tile(gid: u32, flip_x: bool, flip_y: bool, flip_diagonal: bool) -> Tile {
+        Tile { gid: gid, flip_x: flip_x, flip_y: flip_y, flip_diagonal: flip_diagonal }
+    }
+
+    const TMX: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="3" height="2" tilewidth="8" tileheight="8">
+ <properties><property name="title" value="a &amp; b"/></properties>
+ <tileset firstgid="1" name="ground" tilewidth="8" tileheight="8" tilecount="4" columns="2">
+  <image source="ground.png" width="16" height="16"/>
+ </tileset>
+ <tileset firstgid="5" name="props" tilewidth="8" tileheight="8">
+  <image source="props.png" width="34" height="18"/>
+ </tileset>
+ <group name="g" offsetx="4" opacity="0.5">
+  <layer name="csv" width="3" height="2" opacity="0.5">
+   <data encoding="csv">
+1,0,2147483650,
+1073741828,536870917,3758096390
+</data>
+  </layer>
+ </group>
+ <layer name="base64" width="2" height="1">
+  <data encoding="base64">
+   AQAAAAIAAIA=
+  </data>
+ </layer>
+ <objectgroup name="objects">
+  <object id="1" name="door" type="exit" x="1" y="2" width="3" height="4"><ellipse/></object>
+  <object id="2" x="5" y="6"><polygon points="0,0 4,5 -1,2"/></object>
+ </objectgroup>
+</map>"#;
+
+    #[test]
+    fn reads_tmx_layers_and_flip_flags() {
+        let map = TileMap::from_tmx(TMX).unwrap();
+        assert_eq!(map.size, (3, 2));
+        assert_eq!(map.properties.get("title").map(|s| &s[..]), Some("a & b"));
+
+        let csv = map.tile_layer("csv").unwrap();
+        assert_eq!(csv.offset, (4.0, 0.0));
+        assert_eq!(csv.opacity, 0.25);
+        assert_eq!(csv.tiles(), &[tile(1, false, false, false),
+                                  tile(0, false, false, false),
+                                  tile(2, true, false, false),
+                                  tile(4, false, true, false),
+                                  tile(5, false, false, true),
+                                  tile(6, true, true, true)][..]);
+        assert_eq!(csv.tile(2, 1), Some(tile(6, true, true, true)));
+
+        let base64 = map.tile_layer("base64").unwrap();
+        assert_eq!(base64.tiles(), &[tile(1, false, false, false),
+                                     tile(2, true, false, false)][..]);
+
+        let objects = map.object_layer("objects").unwrap();
+        assert_eq!(objects.objects.len(), 2);
+        assert_eq!(objects.objects[0].kind, "exit");
+        assert_eq!(objects.objects[0].shape, ObjectShape::Ellipse);
+        assert_eq!(objects.objects[1].shape,
+                   ObjectShape::Polygon(vec![(0.0, 0.0), (4.0, 5.0), (-1.0, 2.0)]));
+    }
+
+    #[test]
+    fn derives_missing_tile_counts() {
+        let map = TileMap::from_tmx(TMX).unwrap();
+        // 34x18 holds four columns and two rows of 8x8 tiles.
+        assert_eq!(map.tilesets[1].columns, 4);
+        assert_eq!(map.tilesets[1].tile_count, 8);
+        assert_eq!(map.tileset_index(0), None);
+        assert_eq!(map.tileset_index(4), Some(0));
+        assert_eq!(map.tileset_index(5), Some(1));
+        assert_eq!(map.tileset_index(12), Some(1));
+        assert_eq!(map.tileset_index(13), None);
+    }
+
+    #[test]
+    fn unknown_tile_counts_run_to_the_next_tileset() {
+        let mut map = TileMap::from_tmx(TMX).unwrap();
+        map.tilesets[0].tile_count = 0;
+        map.tilesets[1].tile_count = 0;
+        assert_eq!(map.tileset_index(4), Some(0));
+        assert_eq!(map.tileset_index(5), Some(1));
+        assert_eq!(map.tileset_index(1000), Some(1));
+    }
+
+    #[test]
+    fn reads_json_maps() {
+        let map = TileMap::from_json(r#"{
+            "orientation": "orthogonal", "width": 2, "height": 1,
+            "tilewidth": 8, "tileheight": 8,
+            "tilesets": [{"firstgid": 1, "name": "t", "tilewidth": 8, "tileheight": 8,
+                          "image": "t.png", "imagewidth": 16, "imageheight": 24}],
+            "properties": [{"name": "lives", "type": "int", "value": 3}],
+            "layers": [
+                {"type": "tilelayer", "name": "array", "width": 2, "height": 1,
+                 "data": [1, 1073741830]},
+                {"type": "tilelayer", "name": "base64", "width": 2, "height": 1,
+                 "encoding": "base64", "data": "AQAAAAIAAIA="},
+                {"type": "objectgroup", "name": "o", "objects": [
+                    {"id": 1, "x": 1, "y": 1, "polyline": [{"x": 0, "y": 0}, {"x": 2, "y": 3}]}]}
+            ]
+        }"#).unwrap();
+        assert_eq!(map.properties.get("lives").map(|s| &s[..]), Some("3"));
+        assert_eq!(map.tilesets[0].columns, 2);
+        assert_eq!(map.tilesets[0].tile_count, 6);
+        assert_eq!(map.tile_layer("array").unwrap().tiles(),
+                   &[tile(1, false, false, false), tile(6, false, true, false)][..]);
+        assert_eq!(map.tile_layer("base64").unwrap().tiles(),
+                   &[tile(1, false, false, false), tile(2, true, false, false)][..]);
+        assert_eq!(map.object_layer("o").unwrap().objects[0].shape,
+                   ObjectShape::Polyline(vec![(0.0, 0.0), (2.0, 3.0)]));
+    }
+
+    #[test]
+    fn rejects_zero_sized_tiles() {
+        assert!(TileMap::from_json(r#"{"orientation": "orthogonal", "width": 2, "height": 1,
+            "tilewidth": 0, "tileheight": 8, "tilesets": [], "layers": []}"#).is_err());
+        assert!(TileMap::from_tmx(r#"<map orientation="orthogonal" width="2" height="1"
+            tilewidth="8" tileheight="0"></map>"#).is_err());
+        assert!(TileMap::from_tmx(r#"<map orientation="orthogonal" width="2" height="1"
+            tilewidth="8" tileheight="8"><tileset firstgid="1" name="t" tilewidth="0"
+            tileheight="8"/></map>"#).is_err());
+    }
+
+    #[test]
+    fn huge_layers_do_not_overflow() {
+        assert!(check_tile_count(&[0, 0], (65536, 65536), "l").is_err());
+        assert!(check_tile_count(&[0, 0], (2, 1), "l").is_ok());
+    }
+
+    #[test]
+    fn rejects_unsupported_maps() {
+        assert!(TileMap::from_json(r#"{"orientation": "isometric"}"#).is_err());
+        assert!(TileMap::from_tmx(r#"<map orientation="orthogonal" width="2" height="1"
+            tilewidth="8" tileheight="8"><layer name="l" width="2" height="1">
+            <data encoding="base64" compression="zlib">eJw=</data></layer></map>"#).is_err());
+        assert!(TileMap::from_tmx(r#"<map orientation="orthogonal" width="2" height="1"
+            tilewidth="8" tileheight="8"><layer name="l" width="2" height="1">
+            <data encoding="csv">1,2,3</data></layer></map>"#).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! A small XML reader that understands enough of the format to read the
+//! files that Tiled writes.
+//!
+//! Processing instructions, comments and doctypes are skipped, and only the
+//! five predefined entities and numeric character references are decoded.
+
+use super::error::{LuxError, LuxResult};
+
+/// An element along with its attributes, children and text.
+#[derive(Clone, Debug)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+}
+
+impl Element {
+    /// Returns the value of an attribute.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.0 == name).map(|a| &a.1[..])
+    }
+
+    /// Returns the first child with a name.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// Returns every child with a name.
+    pub fn children_named(&self, name: &str) -> Vec<&Element> {
+        self.children.iter().filter(|c| c.name == name).collect()
+    }
+}
+
+/// Parses a document and returns its root element.
+pub fn parse(source: &str) -> LuxResult<Element> {
+    let mut reader = Reader { chars: source.chars().collect(), pos: 0 };
+    try!(reader.skip_misc());
+    let root = try!(reader.element());
+    try!(reader.skip_misc());
+    if reader.pos < reader.chars.len() {
+        return Err(reader.error("content after the root element"));
+    }
+    Ok(root)
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn error(&self, message: &str) -> LuxError {
+        LuxError::SerializationError(format!("Invalid XML at character {}: {}", self.pos, message))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map(char::is_whitespace).unwrap_or(false) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips past the next occurrence of `end`.
+    fn skip_past(&mut self, end: &str) -> LuxResult<()> {
+        while self.pos < self.chars.len() {
+            if self.starts_with(end) {
+                self.pos += end.chars().count();
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+        Err(self.error(&format!("expected `{}`", end)))
+    }
+
+    /// Skips whitespace, comments, processing instructions and doctypes.
+    fn skip_misc(&mut self) -> LuxResult<()> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<?") {
+                try!(self.skip_past("?>"));
+            } else if self.starts_with("<!--") {
+                try!(self.skip_past("-->"));
+            } else if self.starts_with("<!") {
+                try!(self.skip_past(">"));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> LuxResult<String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '=' || c == '>' || c == '/' {
+                break;
+            }
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.chars[start .. self.pos].iter().cloned().collect())
+    }
+
+    fn expect(&mut self, c: char) -> LuxResult<()> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", c)))
+        }
+    }
+
+    fn element(&mut self) -> LuxResult<Element> {
+        try!(self.expect('<'));
+        let name = try!(self.name());
+        let mut element = Element {
+            name: name,
+            attributes: vec![],
+            children: vec![],
+            text: String::new(),
+        };
+
+        // Attributes
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('/') => {
+                    self.pos += 1;
+                    try!(self.expect('>'));
+                    return Ok(element);
+                }
+                Some('>') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => {
+                    let key = try!(self.name());
+                    self.skip_whitespace();
+                    try!(self.expect('='));
+                    self.skip_whitespace();
+                    let quote = match self.peek() {
+                        Some(q) if q == '"' || q == '\'' => q,
+                        _ => return Err(self.error("expected a quoted attribute value")),
+                    };
+                    self.pos += 1;
+                    let start = self.pos;
+                    while self.peek().map(|c| c != quote).unwrap_or(false) {
+                        self.pos += 1;
+                    }
+                    let raw: String = self.chars[start .. self.pos].iter().cloned().collect();
+                    try!(self.expect(quote));
+                    element.attributes.push((key, try!(self.unescape(&raw))));
+                }
+                None => return Err(self.error("unexpected end of document")),
+            }
+        }
+
+        // Content
+        loop {
+            if self.starts_with("</") {
+                self.pos += 2;
+                let end = try!(self.name());
+                if end != element.name {
+                    return Err(self.error(&format!("`{}` is closed by `{}`", element.name, end)));
+                }
+                self.skip_whitespace();
+                try!(self.expect('>'));
+                return Ok(element);
+            } else if self.starts_with("<!--") {
+                try!(self.skip_past("-->"));
+            } else if self.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let start = self.pos;
+                try!(self.skip_past("]]>"));
+                let text: String = self.chars[start .. self.pos - 3].iter().cloned().collect();
+                element.text.push_str(&text);
+            } else if self.starts_with("<?") {
+                try!(self.skip_past("?>"));
+            } else if self.peek() == Some('<') {
+                element.children.push(try!(self.element()));
+            } else if self.peek().is_some() {
+                let start = self.pos;
+                while self.peek().map(|c| c != '<').unwrap_or(false) {
+                    self.pos += 1;
+                }
+                let raw: String = self.chars[start .. self.pos].iter().cloned().collect();
+                element.text.push_str(&try!(self.unescape(&raw)));
+            } else {
+                return Err(self.error(&format!("`{}` is never closed", element.name)));
+            }
+        }
+    }
+
+    fn unescape(&self, raw: &str) -> LuxResult<String> {
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(amp) = rest.find('&') {
+            out.push_str(&rest[.. amp]);
+            rest = &rest[amp ..];
+            let semi = match rest.find(';') {
+                Some(semi) => semi,
+                None => return Err(self.error("unterminated entity")),
+            };
+            let entity = &rest[1 .. semi];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ if entity.starts_with("#x") => {
+                    u32::from_str_radix(&entity[2 ..], 16).ok().and_then(::std::char::from_u32)
+                }
+                _ if entity.starts_with('#') => {
+                    entity[1 ..].parse().ok().and_then(::std::char::from_u32)
+                }
+                _ => None,
+            };
+            match decoded {
+                Some(c) => out.push(c),
+                None => return Err(self.error(&format!("unknown entity `&{};`", entity))),
+            }
+            rest = &rest[semi + 1 ..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn reads_nested_elements_and_attributes() {
+        let root = parse(r#"<?xml version="1.0"?>
+            <!DOCTYPE map>
+            <!-- a comment -->
+            <map width="2" name='single'>
+                <tileset firstgid="1"/>
+                <layer name="a"><data>1,2</data></layer>
+                <layer name="b"/>
+            </map>"#).unwrap();
+        assert_eq!(root.name, "map");
+        assert_eq!(root.attr("width"), Some("2"));
+        assert_eq!(root.attr("name"), Some("single"));
+        assert_eq!(root.attr("height"), None);
+        assert_eq!(root.child("tileset").and_then(|t| t.attr("firstgid")), Some("1"));
+        let layers = root.children_named("layer");
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].child("data").unwrap().text, "1,2");
+        assert!(layers[1].children.is_empty());
+    }
+
+    #[test]
+    fn decodes_entities_and_cdata() {
+        let root = parse("<a v=\"&lt;&amp;&gt; &quot;&apos; &#65;&#x42;\">x &amp; y<![CDATA[<&>]]></a>")
+            .unwrap();
+        assert_eq!(root.attr("v"), Some("<&> \"' AB"));
+        assert_eq!(root.text, "x & y<&>");
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<a v=1/>").is_err());
+        assert!(parse("<a>&nope;</a>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse("").is_err());
+    }
+}
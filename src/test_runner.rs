@@ -62,44 +62,57 @@ fn main() {
 
 
     runner.add_test("text_with_newline", |frame| {
-        //frame.text("Hello\nWorld", 0.0, 0.0).draw().unwrap();
+        frame.draw(Text { text: "Hello\nWorld", .. Default::default() }).unwrap();
     });
 
     runner.add_test("indiv_rotated_squares", |frame| {
-        frame.color(rgb(255, 100, 0));
-
         for i in 0 .. 5 {
             let border = i as f32 * 10.0;
             let pos = i as f32 * 100.0;
-            frame.square(pos, 0.0, 50.0)
-                 .border(border / 2.0, rgb(255, 100, 50))
-                 .rotate_around((12.5, 12.5), PI_4 + 0.2)
-                 .fill_and_stroke();
+            frame.draw(Square {
+                x: pos, y: 0.0,
+                size: 50.0,
+                color: rgb(255, 100, 0),
+                style: DrawStyle::FillAndStroke,
+                stroke_width: border / 2.0,
+                stroke_color: rgb(255, 100, 50),
+                transform: Some(*mat4_id().rotate_around((pos + 12.5, 12.5), PI_4 + 0.2)),
+                .. Default::default()
+            }).unwrap();
         }
     });
 
     runner.add_test("squares", |frame| {
-        frame.color(rgb(255, 0, 0));
-
         for i in 0 .. 5 {
             let border = i as f32 * 10.0;
             let pos = i as f32 * 100.0;
-            frame.square(pos, 0.0, 50.0)
-                 .border(border / 2.0, rgba(0, 0, 255, 100))
-                 .fill_and_stroke();
+            frame.draw(Square {
+                x: pos, y: 0.0,
+                size: 50.0,
+                color: rgb(255, 0, 0),
+                style: DrawStyle::FillAndStroke,
+                stroke_width: border / 2.0,
+                stroke_color: rgba(0, 0, 255, 100),
+                .. Default::default()
+            }).unwrap();
         }
     });
 
     runner.add_test("rotated_squares", |frame| {
         frame.rotate(0.5);
-        frame.color(rgb(255, 0, 0));
 
         for i in 0 .. 5 {
             let border = i as f32 * 10.0;
             let pos = i as f32 * 100.0;
-            frame.square(pos, 0.0, 50.0)
-                 .border(border / 2.0, rgba(0, 0, 255, 100))
-                 .fill_and_stroke();
+            frame.draw(Square {
+                x: pos, y: 0.0,
+                size: 50.0,
+                color: rgb(255, 0, 0),
+                style: DrawStyle::FillAndStroke,
+                stroke_width: border / 2.0,
+                stroke_color: rgba(0, 0, 255, 100),
+                .. Default::default()
+            }).unwrap();
         }
     });
 